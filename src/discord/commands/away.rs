@@ -25,7 +25,7 @@ pub async fn away(
         let user_timezone_clone = user_timezone.clone();
         discord::util::update_user_config(ctx, guild_id, user_id, move |c| {
             let mut tz_guard = user_timezone_clone.lock()?;
-            *tz_guard = Some(c.timezone.clone());
+            *tz_guard = Some(c.timezone);
             Ok(())
         })
         .await?;
@@ -45,7 +45,7 @@ pub async fn away(
     {
         let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
         let user_id: model::DiscordUserId = ctx.author().into();
-        let user_back_clone = user_back.clone();
+        let user_back_clone = user_back;
         discord::util::update_user_config(ctx, guild_id, user_id, move |c| {
             c.away_until = Some(user_back_clone);
             Ok(())
@@ -55,7 +55,7 @@ pub async fn away(
 
     ctx.send(
         poise::CreateReply::default()
-            .content(&format!(
+            .content(format!(
                 ":white_check_mark: You are now away, and will be back on {}",
                 user_back
             ))
@@ -0,0 +1,70 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use poise::serenity_prelude as serenity;
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+/// Tell fizz when to escalate PRs that have been waiting too long for review.
+///
+/// Escalations go to the channel if one is given, or else to the leads in the
+/// report channel. They repeat, less and less often, until the review happens.
+/// Use 0 workdays to stop escalating.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn escalate_reviews_after(
+    ctx: DiscordContext<'_>,
+    #[description = "Workdays a review can wait before it is escalated, counted with the reviewer's workdays"]
+    workdays: u32,
+    #[description = "The channel to escalate in (default: ping the leads in the report channel)"]
+    #[channel_types("Text")]
+    channel: Option<serenity::Channel>,
+) -> Result<(), DiscordError> {
+    let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+    let guild_channel = match &channel {
+        Some(serenity::Channel::Guild(guild_channel)) => Some(guild_channel),
+        Some(_) => return Err("Unexpected channel type".into()),
+        None => None,
+    };
+
+    discord::util::update_guild_config(ctx, guild_id, |c| {
+        c.escalation = if workdays == 0 {
+            model::EscalationConfig::default()
+        } else {
+            model::EscalationConfig {
+                after_workdays: Some(workdays),
+                channel_id: guild_channel.map(|ch| ch.into()).unwrap_or_default(),
+                channel_name: guild_channel
+                    .map(|ch| ch.name().to_owned())
+                    .unwrap_or_default(),
+            }
+        };
+        Ok(())
+    })
+    .await?;
+
+    let reply = match guild_channel {
+        _ if workdays == 0 => {
+            ":white_check_mark: PRs waiting for review will no longer be escalated".to_string()
+        }
+        Some(guild_channel) => format!(
+            ":white_check_mark: PRs waiting for review more than {} workdays will be escalated \
+            in channel #{}",
+            workdays,
+            guild_channel.name()
+        ),
+        None => format!(
+            ":white_check_mark: PRs waiting for review more than {} workdays will be escalated \
+            to the leads",
+            workdays
+        ),
+    };
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,55 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use chrono::Utc;
+
+use crate::discord::{DiscordContext, DiscordError};
+
+/// Show how much of the Github API quota is left.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn github_quota(ctx: DiscordContext<'_>) -> Result<(), DiscordError> {
+    let github = &ctx.data().github;
+    // Asking Github doesn't use up any quota, but if it fails, the last known
+    // state is better than nothing.
+    let (rate_limit, note) = match github.refresh_rate_limit().await {
+        Ok(rate_limit) => (rate_limit, ""),
+        Err(e) => {
+            eprintln!("ERROR: getting Github rate limit {}", e);
+            (
+                github.rate_limit(),
+                " (unable to reach Github, this may be out of date)",
+            )
+        }
+    };
+
+    let now = Utc::now();
+    let mut reply = format!(":bar_chart: Github API quota{}:", note);
+    match (rate_limit.remaining, rate_limit.limit) {
+        (Some(remaining), Some(limit)) => {
+            reply.push_str(&format!("\n* {} of {} requests left", remaining, limit))
+        }
+        (Some(remaining), None) => reply.push_str(&format!("\n* {} requests left", remaining)),
+        _ => reply.push_str("\n* Unknown, no requests have been made yet"),
+    }
+    if let Some(reset) = rate_limit.reset.filter(|reset| *reset > now) {
+        reply.push_str(&format!(
+            "\n* Replenished in {} minutes",
+            (reset - now).num_minutes() + 1
+        ));
+    }
+    if let Some(until) = rate_limit.exhausted_until(now) {
+        reply.push_str(&format!(
+            "\n* :pause_button: Watching Github is paused for {} minutes",
+            (until - now).num_minutes() + 1
+        ));
+    }
+
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -14,15 +14,47 @@ pub async fn help(ctx: DiscordContext<'_>) -> Result<(), DiscordError> {
 * To get an alert fresh in the morning, tell me your timezone with `/fizz my_timezone_is <timezone>`. \n\
 * If you have different workdays than Monday to Friday, you can tell me with `/fizz my_workdays_are <days>`. \n\
 * To adjust at what times you will receive PR review report, you can use `/fizz my_report_times_are <times>`. \n\
+* To get your reports in direct messages instead of the report channel, or in both, you can use \
+`/fizz my_reports_are_sent_to`. \n\
+* Reviews waiting longer than 3 days are marked as overdue, which you can change with \
+`/fizz my_reviews_are_overdue_after <days>`. \n\
+* To also hear about the review status of your own PRs, you can use `/fizz my_pr_status_report_is True`. \n\
+* If you don't review PRs until their CI passes, you can hide them with `/fizz my_failing_prs_are_hidden True`. \n\
 * If you are a project lead and want to get pings for open leads issues, you can use `/fizz my_role_is_lead True`. \n\
+* If you help find reviewers for PRs that have none, you can get them in your reports with \
+`/fizz my_role_is_triager True`. \n\
+* To hear about every PR that changes some paths, like `toolchain/check/**`, you can use \
+`/fizz my_watched_paths_are <paths>`. \n\
+* To get the results of a Github search in your reports, you can save it with \
+`/fizz my_search_is <name> <query>`. \n\
+* PRs you reviewed show up again in your report when their author pushes new commits, until you \
+review them again. \n\
+* PRs without a reviewer that change files you own in the repository's CODEOWNERS show up in your \
+report. \n\
+* Reports edit your earlier messages in place, and only ping you when something new shows up. To be \
+pinged by your next report anyway, use `/fizz ping_me_again`. \n\
 * If you will be away and want to pause notifications, you can tell me with `/fizz away <number of days>`. \n\
 * If you come back early from `/fizz away` and want to resume notifications, you can tell me with `/fizz back`. \n\
 * If you ever want to see what your current settings are, use `/fizz whoami`. \n\
 * And you can make me forget everything about you with `/fizz remove_me`. \n\
 \n\
-I can't start alerting about GitHub PRs until I know which repository to watch, and where to send \
+I can't start alerting about GitHub PRs until I know which repositories to watch, and where to send \
 messages. \n\
-* An administrator can set this up with `/fizz setup`.\n\
+* An administrator can set this up with `/fizz setup`, once for each repository.\n\
+* Reviews requested from a Github team reach its members once an administrator describes the team \
+with `/fizz team_members_are`.\n\
+* An administrator can have each user's reports posted in a thread of their own in the report \
+channel with `/fizz report_threads_are`.\n\
+* An administrator can have reports posted as embeds rather than text with \
+`/fizz report_format_is`.\n\
+* An administrator can change which Github labels mark leads issues, and how urgent they are, \
+with `/fizz issue_label_is`.\n\
+* An administrator can have PRs that wait too long for review escalated to the leads, or to a \
+channel, with `/fizz escalate_reviews_after`.\n\
+* An administrator can have the results of a Github search reported in the report channel with \
+`/fizz search_is`.\n\
+* If reports stop arriving, an administrator can check whether fizz has run out of Github API \
+quota with `/fizz github_quota`.\n\
 \n\
 Note that any information provided to me will be saved unless `remove_me` is used to remove it. \
 Any such information is made available to the bot operators, {}, for the purpose of running me, the bot.
@@ -0,0 +1,109 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::sync::{Arc, Mutex};
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+#[derive(poise::ChoiceParameter)]
+pub enum LabelMeaning {
+    #[name = "Marks leads issues"]
+    LeadsIssue,
+    #[name = "Report immediately"]
+    Immediate,
+    #[name = "Report in every report"]
+    EveryReport,
+    #[name = "Report weekly"]
+    Weekly,
+    #[name = "Never report"]
+    Never,
+    #[name = "Nothing (forget the label)"]
+    Nothing,
+}
+
+fn format_urgency(urgency: model::Urgency) -> &'static str {
+    match urgency {
+        model::Urgency::Immediate => "reported immediately",
+        model::Urgency::EveryReport => "reported in every report",
+        model::Urgency::Weekly => "reported weekly",
+        model::Urgency::Never => "never reported",
+    }
+}
+
+/// Tell fizz what a Github label on issues means.
+///
+/// One label marks the issues for the leads, and other labels say how urgent
+/// those issues are. Use `*` as the label for leads issues without any urgency
+/// label.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn issue_label_is(
+    ctx: DiscordContext<'_>,
+    #[description = "The Github label, or `*` for leads issues without an urgency label"]
+    label: String,
+    #[description = "What the label means"] meaning: LabelMeaning,
+) -> Result<(), DiscordError> {
+    let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+    let label = label.trim().to_string();
+    if label.is_empty() {
+        return Err("Missing label".into());
+    }
+
+    let urgency = match meaning {
+        LabelMeaning::LeadsIssue | LabelMeaning::Nothing => None,
+        LabelMeaning::Immediate => Some(model::Urgency::Immediate),
+        LabelMeaning::EveryReport => Some(model::Urgency::EveryReport),
+        LabelMeaning::Weekly => Some(model::Urgency::Weekly),
+        LabelMeaning::Never => Some(model::Urgency::Never),
+    };
+    if label == "*" && urgency.is_none() {
+        return Err("Leads issues without an urgency label need an urgency".into());
+    }
+
+    let labels = Arc::new(Mutex::new(model::IssueLabels::default()));
+    let labels_clone = labels.clone();
+    discord::util::update_guild_config(ctx, guild_id, move |c| {
+        let issue_labels = &mut c.issue_labels;
+        match (label.as_str(), meaning, urgency) {
+            ("*", _, Some(urgency)) => issue_labels.unlabeled = urgency,
+            (_, LabelMeaning::LeadsIssue, _) => issue_labels.leads = label,
+            (_, _, Some(urgency)) => {
+                issue_labels.urgency.insert(label, urgency);
+            }
+            (_, _, None) => {
+                issue_labels.urgency.remove(&label);
+            }
+        }
+        *labels_clone.lock()? = issue_labels.clone();
+        Ok(())
+    })
+    .await?;
+
+    let labels = labels.lock()?.clone();
+    let mut reply = format!(
+        ":white_check_mark: Leads issues are labelled '{}'",
+        labels.leads
+    );
+    let mut urgency_labels: Vec<_> = labels.urgency.iter().collect();
+    urgency_labels.sort_by_key(|(label, urgency)| (**urgency, label.as_str()));
+    for (label, urgency) in urgency_labels {
+        reply.push_str(&format!(
+            "\n* With '{}', they are {}",
+            label,
+            format_urgency(*urgency)
+        ));
+    }
+    reply.push_str(&format!(
+        "\n* Otherwise, they are {}",
+        format_urgency(labels.unlabeled)
+    ));
+
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
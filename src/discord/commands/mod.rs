@@ -4,16 +4,31 @@
 
 mod away;
 mod back;
+mod escalate_reviews_after;
+mod github_quota;
 mod help;
+mod issue_label_is;
+mod my_failing_prs_are_hidden;
 mod my_github_is;
+mod my_pr_status_report_is;
 mod my_report_times_are;
+mod my_reports_are_sent_to;
+mod my_reviews_are_overdue_after;
 mod my_role_is_lead;
+mod my_role_is_triager;
+mod my_search_is;
 mod my_timezone_is;
+mod my_watched_paths_are;
 mod my_workdays_are;
 mod ping;
+mod ping_me_again;
 mod remove_me;
 mod report_all;
+mod report_format_is;
+mod report_threads_are;
+mod search_is;
 mod setup;
+mod team_members_are;
 mod wake;
 mod whoami;
 mod whois_everyone;
@@ -28,16 +43,31 @@ use crate::discord::{DiscordContext, DiscordData, DiscordError};
     subcommands(
         "away::away",
         "back::back",
+        "escalate_reviews_after::escalate_reviews_after",
+        "github_quota::github_quota",
         "help::help",
+        "issue_label_is::issue_label_is",
+        "my_failing_prs_are_hidden::my_failing_prs_are_hidden",
         "my_report_times_are::my_report_times_are",
+        "my_reports_are_sent_to::my_reports_are_sent_to",
+        "my_reviews_are_overdue_after::my_reviews_are_overdue_after",
         "my_role_is_lead::my_role_is_lead",
+        "my_role_is_triager::my_role_is_triager",
+        "my_search_is::my_search_is",
         "my_github_is::my_github_is",
+        "my_pr_status_report_is::my_pr_status_report_is",
         "my_workdays_are::my_workdays_are",
         "my_timezone_is::my_timezone_is",
+        "my_watched_paths_are::my_watched_paths_are",
         "ping::ping",
+        "ping_me_again::ping_me_again",
         "remove_me::remove_me",
         "report_all::report_all",
+        "report_format_is::report_format_is",
+        "report_threads_are::report_threads_are",
+        "search_is::search_is",
         "setup::setup",
+        "team_members_are::team_members_are",
         "wake::wake",
         "whoami::whoami",
         "whois_everyone::whois_everyone"
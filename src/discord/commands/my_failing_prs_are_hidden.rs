@@ -0,0 +1,34 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+/// Tell fizz whether to leave PRs with failing CI out of your review reports.
+///
+/// Use `/whoami`` to find out if PRs with failing CI are currently hidden.
+#[poise::command(slash_command, guild_only)]
+pub async fn my_failing_prs_are_hidden(
+    ctx: DiscordContext<'_>,
+    #[description = "Whether you want to skip reviews of PRs with failing CI"] true_or_false: bool,
+) -> Result<(), DiscordError> {
+    {
+        let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+        let user_id: model::DiscordUserId = ctx.author().into();
+        discord::util::update_user_config(ctx, guild_id, user_id, move |c| {
+            c.hide_failing_ci = true_or_false;
+            Ok(())
+        })
+        .await?;
+    }
+
+    let reply = if true_or_false {
+        ":white_check_mark: PRs with failing CI will be left out of your reports"
+    } else {
+        ":white_check_mark: PRs with failing CI will be included in your reports"
+    };
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,47 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+/// Tell fizz whether to report on the review status of PRs you authored.
+///
+/// Use `/whoami`` to find out if you currently get reports on your PRs.
+#[poise::command(slash_command, guild_only)]
+pub async fn my_pr_status_report_is(
+    ctx: DiscordContext<'_>,
+    #[description = "Whether you want reports on your own PRs"] true_or_false: bool,
+    #[description = "Days a PR can wait for review before it is called out (default: 3)"]
+    stale_after_days: Option<u32>,
+) -> Result<(), DiscordError> {
+    let stale_days = std::sync::Arc::new(std::sync::Mutex::new(0));
+
+    {
+        let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+        let user_id: model::DiscordUserId = ctx.author().into();
+        let stale_days_clone = stale_days.clone();
+        discord::util::update_user_config(ctx, guild_id, user_id, move |c| {
+            c.author_report = true_or_false;
+            if let Some(days) = stale_after_days {
+                c.author_report_stale_days = days;
+            }
+            *stale_days_clone.lock()? = c.author_report_stale_days;
+            Ok(())
+        })
+        .await?;
+    }
+
+    let reply = if true_or_false {
+        format!(
+            ":white_check_mark: Your reports will include your own PRs, calling out those \
+            waiting for review more than {} days",
+            stale_days.lock()?
+        )
+    } else {
+        ":white_check_mark: Your reports will not include your own PRs".to_string()
+    };
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
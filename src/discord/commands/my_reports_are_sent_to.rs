@@ -0,0 +1,61 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+#[derive(poise::ChoiceParameter)]
+pub enum ReportDelivery {
+    #[name = "The report channel"]
+    Channel,
+    #[name = "Direct messages"]
+    Dm,
+    #[name = "Both"]
+    Both,
+}
+
+impl From<ReportDelivery> for model::Delivery {
+    fn from(delivery: ReportDelivery) -> Self {
+        match delivery {
+            ReportDelivery::Channel => model::Delivery::Channel,
+            ReportDelivery::Dm => model::Delivery::Dm,
+            ReportDelivery::Both => model::Delivery::Both,
+        }
+    }
+}
+
+pub fn format_delivery(delivery: model::Delivery) -> &'static str {
+    match delivery {
+        model::Delivery::Channel => "the report channel",
+        model::Delivery::Dm => "your direct messages",
+        model::Delivery::Both => "the report channel and your direct messages",
+    }
+}
+
+/// Tell fizz whether to send your reports to the report channel, to you
+/// directly, or both.
+#[poise::command(slash_command, guild_only)]
+pub async fn my_reports_are_sent_to(
+    ctx: DiscordContext<'_>,
+    #[description = "Where to send your reports"] delivery: ReportDelivery,
+) -> Result<(), DiscordError> {
+    let delivery: model::Delivery = delivery.into();
+    {
+        let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+        let user_id: model::DiscordUserId = ctx.author().into();
+        discord::util::update_user_config(ctx, guild_id, user_id, |c| {
+            c.delivery = delivery;
+            Ok(())
+        })
+        .await?;
+    }
+
+    let reply = format!(
+        ":white_check_mark: Your reports will be sent to {}",
+        format_delivery(delivery)
+    );
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,34 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+/// Tell fizz how many days a review requested from you can wait before it is
+/// marked as overdue.
+///
+/// Use `/whoami`` to find out when your reviews are currently overdue.
+#[poise::command(slash_command, guild_only)]
+pub async fn my_reviews_are_overdue_after(
+    ctx: DiscordContext<'_>,
+    #[description = "Days after a review is requested that it becomes overdue"] days: u32,
+) -> Result<(), DiscordError> {
+    {
+        let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+        let user_id: model::DiscordUserId = ctx.author().into();
+        discord::util::update_user_config(ctx, guild_id, user_id, move |c| {
+            c.review_overdue_days = days;
+            Ok(())
+        })
+        .await?;
+    }
+
+    let reply = format!(
+        ":white_check_mark: Reviews requested from you will be marked as overdue after {} days",
+        days
+    );
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -25,9 +25,9 @@ pub async fn my_role_is_lead(
     }
 
     let reply = if true_or_false {
-        format!(":white_check_mark: You will be pinged for leads issues")
+        ":white_check_mark: You will be pinged for leads issues".to_string()
     } else {
-        format!(":white_check_mark: You will not be pinged for leads issues")
+        ":white_check_mark: You will not be pinged for leads issues".to_string()
     };
     ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
         .await?;
@@ -0,0 +1,34 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+/// Tell fizz whether you triage PRs that have no reviewer.
+///
+/// Use `/whoami`` to find out if you are currently set as a triager.
+#[poise::command(slash_command, guild_only)]
+pub async fn my_role_is_triager(
+    ctx: DiscordContext<'_>,
+    #[description = "Whether you want reports of PRs that have no reviewer"] true_or_false: bool,
+) -> Result<(), DiscordError> {
+    {
+        let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+        let user_id: model::DiscordUserId = ctx.author().into();
+        discord::util::update_user_config(ctx, guild_id, user_id, move |c| {
+            c.triager = true_or_false;
+            Ok(())
+        })
+        .await?;
+    }
+
+    let reply = if true_or_false {
+        ":white_check_mark: Your reports will include PRs that have no reviewer".to_string()
+    } else {
+        ":white_check_mark: Your reports will not include PRs that have no reviewer".to_string()
+    };
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
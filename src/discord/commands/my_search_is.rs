@@ -0,0 +1,128 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::github;
+use crate::model;
+
+#[derive(poise::ChoiceParameter)]
+pub enum SearchCadence {
+    #[name = "Daily"]
+    Daily,
+    #[name = "Weekly"]
+    Weekly,
+}
+
+impl From<SearchCadence> for model::Cadence {
+    fn from(cadence: SearchCadence) -> Self {
+        match cadence {
+            SearchCadence::Daily => model::Cadence::Daily,
+            SearchCadence::Weekly => model::Cadence::Weekly,
+        }
+    }
+}
+
+pub fn format_cadence(cadence: model::Cadence) -> &'static str {
+    match cadence {
+        model::Cadence::Daily => "daily",
+        model::Cadence::Weekly => "weekly",
+    }
+}
+
+/// Checks that Github accepts the query, and describes how many results it
+/// has now.
+pub async fn try_search(
+    ctx: DiscordContext<'_>,
+    guild_id: &model::DiscordGuildId,
+    query: &str,
+) -> Result<String, DiscordError> {
+    let repos = {
+        let cfg_guard = ctx.data().cfg.lock().await;
+        match cfg_guard.guilds.get(guild_id) {
+            Some(guild_config) => guild_config.repos.clone(),
+            None => Vec::new(),
+        }
+    };
+    match github::search(ctx.data().github.clone(), query, &repos).await {
+        Ok(results) if results.len() >= github::MAX_SEARCH_RESULTS => {
+            Ok(format!("at least {} results", github::MAX_SEARCH_RESULTS))
+        }
+        Ok(results) => Ok(format!("{} results", results.len())),
+        Err(e) => Err(DiscordError::new(
+            format!("Github was unable to search for '{}'", query),
+            e,
+        )),
+    }
+}
+
+/// Tell fizz about a Github search to report on in a section of your reports.
+///
+/// The search is limited to the watched repositories, unless it has a `repo:`,
+/// `org:` or `user:` qualifier. Use `-` as the query to forget the search.
+#[poise::command(slash_command, guild_only)]
+pub async fn my_search_is(
+    ctx: DiscordContext<'_>,
+    #[description = "A name for the search, used as the header of its section"] name: String,
+    #[description = "The Github search query (e.g. 'is:issue label:\"good first issue\" no:assignee')"]
+    query: String,
+    #[description = "How often to report the search (default: Daily)"] cadence: Option<
+        SearchCadence,
+    >,
+) -> Result<(), DiscordError> {
+    let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+    let user_id: model::DiscordUserId = ctx.author().into();
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Missing search name".into());
+    }
+    let query = query.trim().to_string();
+
+    if query == "-" {
+        // The search's last report goes too, from the channel and from DMs.
+        let header = discord::tasks::search_header(&name, Some(&user_id));
+        let mut posted = Vec::new();
+        discord::util::update_guild_config(ctx, guild_id, |c| {
+            if let Some(user_config) = c.users.get_mut(&user_id) {
+                user_config.saved_searches.retain(|s| s.name != name);
+                posted.extend(user_config.dm_report_messages.remove(&header));
+            }
+            posted.extend(c.report_messages.remove(&header));
+            Ok(())
+        })
+        .await?;
+        discord::util::delete_posted_sections(ctx.http(), posted).await?;
+
+        let reply = format!(":white_check_mark: Forgot the search '{}'", name);
+        ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let found = try_search(ctx, &guild_id, &query).await?;
+    let cadence: model::Cadence = cadence.map(Into::into).unwrap_or_default();
+    {
+        let saved_search = model::SavedSearch {
+            name: name.clone(),
+            query,
+            cadence,
+            last_reported_at: None,
+        };
+        discord::util::update_user_config(ctx, guild_id, user_id, move |c| {
+            c.saved_searches.retain(|s| s.name != saved_search.name);
+            c.saved_searches.push(saved_search);
+            Ok(())
+        })
+        .await?;
+    }
+
+    let reply = format!(
+        ":white_check_mark: Your reports will include the search '{}' {}, which finds {} now",
+        name,
+        format_cadence(cadence),
+        found
+    );
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,54 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+/// Tell fizz the paths you want to hear about every PR for. It replaces any
+/// previous paths.
+///
+/// You hear about these PRs even when you aren't asked to review. Paths are
+/// written like in CODEOWNERS, such as `toolchain/check/**` or
+/// `*.md`. Use `-` to stop watching paths. Use `/whoami`` to find the paths
+/// you currently watch.
+#[poise::command(slash_command, guild_only)]
+pub async fn my_watched_paths_are(
+    ctx: DiscordContext<'_>,
+    #[description = "Paths separated by commas (e.g. 'toolchain/check/**,*.md'), or '-' for none"]
+    paths: String,
+) -> Result<(), DiscordError> {
+    let paths: Vec<String> = if paths.trim() == "-" {
+        Vec::new()
+    } else {
+        paths
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    {
+        let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+        let user_id: model::DiscordUserId = ctx.author().into();
+        let paths_clone = paths.clone();
+        discord::util::update_user_config(ctx, guild_id, user_id, move |c| {
+            c.watched_paths = paths_clone;
+            Ok(())
+        })
+        .await?;
+    }
+
+    let reply = if paths.is_empty() {
+        ":white_check_mark: You are not watching any paths".to_string()
+    } else {
+        format!(
+            ":white_check_mark: You will hear about PRs that change: {}",
+            paths.join(", ")
+        )
+    };
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
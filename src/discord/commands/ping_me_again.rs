@@ -0,0 +1,32 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord;
+use crate::discord::{DiscordContext, DiscordError};
+use crate::model;
+
+/// Have your next report posted afresh, pinging you, even if nothing is new.
+///
+/// Otherwise reports edit the earlier messages in place, and only ping you
+/// when they list something new.
+#[poise::command(slash_command, guild_only)]
+pub async fn ping_me_again(ctx: DiscordContext<'_>) -> Result<(), DiscordError> {
+    {
+        let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+        let user_id: model::DiscordUserId = ctx.author().into();
+        discord::util::update_user_config(ctx, guild_id, user_id, |c| {
+            c.ping_next_report = true;
+            Ok(())
+        })
+        .await?;
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(":white_check_mark: Your next report will ping you")
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
@@ -6,16 +6,43 @@ use crate::discord::{self, DiscordContext, DiscordError};
 use crate::model;
 
 /// Tell fizz to forget everything about you.
+///
+/// The reports that fizz posted for you are deleted too.
 #[poise::command(slash_command, guild_only)]
 pub async fn remove_me(ctx: DiscordContext<'_>) -> Result<(), DiscordError> {
     let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
     let user_id: model::DiscordUserId = ctx.author().into();
-    discord::util::update_guild_config(ctx, guild_id, move |c| {
-        c.users.remove(&user_id);
+    let mut posted = Vec::new();
+    let mut thread_id = None;
+    discord::util::update_guild_config(ctx, guild_id, |c| {
+        if let Some(user_config) = c.users.remove(&user_id) {
+            posted.extend(user_config.dm_report_messages.into_values());
+            thread_id = user_config.report_thread.map(|thread| thread.thread_id);
+        }
+        c.report_messages.retain(|_, section| {
+            if section.discord_user_id.as_ref() == Some(&user_id) {
+                posted.push(section.clone());
+                false
+            } else {
+                true
+            }
+        });
         Ok(())
     })
     .await?;
 
+    if let Some(thread_id) = &thread_id {
+        match ctx
+            .http()
+            .remove_thread_channel_member(thread_id.into(), (&user_id).into())
+            .await
+        {
+            Err(e) if !discord::util::is_not_found(&e) => return Err(e.into()),
+            _ => {}
+        }
+    }
+    discord::util::delete_posted_sections(ctx.http(), posted).await?;
+
     let reply = ":white_check_mark: You have been removed.";
     ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
         .await?;
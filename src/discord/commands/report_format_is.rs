@@ -0,0 +1,53 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+#[derive(poise::ChoiceParameter)]
+pub enum Format {
+    #[name = "Text"]
+    Text,
+    #[name = "Embeds"]
+    Embeds,
+}
+
+impl From<Format> for model::ReportFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Text => model::ReportFormat::Text,
+            Format::Embeds => model::ReportFormat::Embeds,
+        }
+    }
+}
+
+/// Tell fizz whether to post reports as plain text or as embeds.
+///
+/// Embeds have a field for each PR or issue, and are colored by urgency.
+/// Reports that were already posted change format when they are next updated.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn report_format_is(
+    ctx: DiscordContext<'_>,
+    #[description = "How to render reports"] format: Format,
+) -> Result<(), DiscordError> {
+    let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+    let report_format: model::ReportFormat = format.into();
+    discord::util::update_guild_config(ctx, guild_id, |c| {
+        c.report_format = report_format;
+        Ok(())
+    })
+    .await?;
+
+    let reply = match report_format {
+        model::ReportFormat::Text => ":white_check_mark: Reports will be posted as text",
+        model::ReportFormat::Embeds => ":white_check_mark: Reports will be posted as embeds",
+    };
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+#[derive(poise::ChoiceParameter)]
+pub enum ThreadKind {
+    #[name = "Off"]
+    Off,
+    #[name = "Public"]
+    Public,
+    #[name = "Private"]
+    Private,
+}
+
+impl From<ThreadKind> for model::ReportThreads {
+    fn from(kind: ThreadKind) -> Self {
+        match kind {
+            ThreadKind::Off => model::ReportThreads::Off,
+            ThreadKind::Public => model::ReportThreads::Public,
+            ThreadKind::Private => model::ReportThreads::Private,
+        }
+    }
+}
+
+/// Tell fizz whether to post each user's reports in a thread of their own.
+///
+/// The threads are in the report channel. Private threads are only seen by
+/// their user, and by those that can manage threads.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn report_threads_are(
+    ctx: DiscordContext<'_>,
+    #[description = "The kind of thread for each user's reports, or Off to post in the channel"]
+    kind: ThreadKind,
+) -> Result<(), DiscordError> {
+    let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+    let report_threads: model::ReportThreads = kind.into();
+    discord::util::update_guild_config(ctx, guild_id, |c| {
+        c.report_threads = report_threads;
+        Ok(())
+    })
+    .await?;
+
+    let reply = match report_threads {
+        model::ReportThreads::Off => {
+            ":white_check_mark: Reports will be posted in the report channel"
+        }
+        model::ReportThreads::Public => {
+            ":white_check_mark: Reports will be posted in a public thread for each user"
+        }
+        model::ReportThreads::Private => {
+            ":white_check_mark: Reports will be posted in a private thread for each user"
+        }
+    };
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,75 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use super::my_search_is::{format_cadence, try_search, SearchCadence};
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::model;
+
+/// Tell fizz about a Github search to report on in the report channel.
+///
+/// The search is limited to the watched repositories, unless it has a `repo:`,
+/// `org:` or `user:` qualifier. Use `-` as the query to forget the search.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn search_is(
+    ctx: DiscordContext<'_>,
+    #[description = "A name for the search, used as the header of its section"] name: String,
+    #[description = "The Github search query (e.g. 'is:issue label:\"good first issue\" no:assignee')"]
+    query: String,
+    #[description = "How often to report the search (default: Daily)"] cadence: Option<
+        SearchCadence,
+    >,
+) -> Result<(), DiscordError> {
+    let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err("Missing search name".into());
+    }
+    let query = query.trim().to_string();
+
+    if query == "-" {
+        // The search's last report goes too.
+        let header = discord::tasks::search_header(&name, None);
+        let mut posted = None;
+        discord::util::update_guild_config(ctx, guild_id, |c| {
+            c.saved_searches.retain(|s| s.name != name);
+            posted = c.report_messages.remove(&header);
+            Ok(())
+        })
+        .await?;
+        discord::util::delete_posted_sections(ctx.http(), posted).await?;
+
+        let reply = format!(":white_check_mark: Forgot the search '{}'", name);
+        ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let found = try_search(ctx, &guild_id, &query).await?;
+    let cadence: model::Cadence = cadence.map(Into::into).unwrap_or_default();
+    let reply = format!(
+        ":white_check_mark: The search '{}' will be reported {} in the report channel, and finds {} now",
+        name,
+        format_cadence(cadence),
+        found
+    );
+    discord::util::update_guild_config(ctx, guild_id, |c| {
+        c.saved_searches.retain(|s| s.name != name);
+        c.saved_searches.push(model::SavedSearch {
+            name,
+            query,
+            cadence,
+            last_reported_at: None,
+        });
+        Ok(())
+    })
+    .await?;
+
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
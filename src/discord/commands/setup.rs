@@ -8,6 +8,9 @@ use crate::discord::{self, DiscordContext, DiscordError};
 use crate::model;
 
 /// Asks the bot to join a channel and report on active PRs there.
+///
+/// Each use adds a repository to report on, unless `remove` is set, in which
+/// case the repository is no longer reported on.
 #[poise::command(
     slash_command,
     guild_only,
@@ -20,34 +23,60 @@ pub async fn setup(
     channel: serenity::Channel,
     #[description = "The GitHub repository owner/organization"] repo_owner: String,
     #[description = "The GitHub repository name"] repo_name: String,
+    #[description = "Stop reporting on the repository (default: False)"] remove: Option<bool>,
 ) -> Result<(), DiscordError> {
+    let remove = remove.unwrap_or(false);
     let serenity::Channel::Guild(guild_channel) = &channel else {
         return Err("Unexpected channel type".into());
     };
 
     let guild_id: model::DiscordGuildId = guild_channel.guild_id.into();
     let channel_id: model::DiscordChannelId = guild_channel.into();
+    let repo = model::RepoConfig {
+        owner: repo_owner.trim().to_string(),
+        name: repo_name.trim().to_string(),
+    };
+    if repo.owner.is_empty() || repo.name.is_empty() {
+        return Err("Missing repository owner or name".into());
+    }
     println!(
-        "Asked to report PRS in guild {} channel {} ({}) by {}",
+        "Asked to {} PRS for {} in guild {} channel {} ({}) by {}",
+        if remove { "stop reporting" } else { "report" },
+        repo,
         guild_id.0,
         channel_id.1,
         guild_channel.name(),
         ctx.author().name,
     );
 
+    // Collect the watched repositories for the reply.
+    let repos = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let repos_clone = repos.clone();
     discord::util::update_guild_config(ctx, guild_id, |c| {
-        c.repo_owner = repo_owner;
-        c.repo_name = repo_name;
+        c.repos.retain(|r| !r.is(&repo.owner, &repo.name));
+        if !remove {
+            c.repos.push(repo);
+        }
         c.report_channel_id = channel_id;
         c.report_channel_name = guild_channel.name().to_owned();
+        *repos_clone.lock()? = c.repos.clone();
         Ok(())
     })
     .await?;
 
-    let reply = format!(
+    let mut reply = format!(
         ":white_check_mark: Reporting PRs in channel #{}",
         guild_channel.name()
     );
+    let repos = std::mem::take(&mut *repos.lock()?);
+    if repos.is_empty() {
+        reply.push_str(", but there are no repositories to report on");
+    } else {
+        reply.push_str(" from:");
+        for r in &repos {
+            reply.push_str(&format!("\n* {}", r));
+        }
+    }
     ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
         .await?;
     Ok(())
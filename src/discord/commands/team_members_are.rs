@@ -0,0 +1,101 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use crate::discord::{self, DiscordContext, DiscordError};
+use crate::github;
+use crate::model;
+
+/// Tell fizz the members of a Github team, who get reviews requested from the
+/// team.
+///
+/// Leave out the members to fetch them from Github, in the team's organization,
+/// or the organization of the first watched repository if the team doesn't say.
+/// Use `-` as the members to forget the team.
+#[poise::command(
+    slash_command,
+    guild_only,
+    default_member_permissions = "ADMINISTRATOR"
+)]
+pub async fn team_members_are(
+    ctx: DiscordContext<'_>,
+    #[description = "The Github team (e.g. 'toolchain-reviewers' or '@org/toolchain-reviewers')"]
+    team: String,
+    #[description = "The team's Github usernames, separated by commas (or empty to ask Github)"]
+    github_names: Option<String>,
+) -> Result<(), DiscordError> {
+    let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
+    let team_slug = github::normalize_team_slug(&team);
+    if team_slug.is_empty() {
+        return Err("Missing team name".into());
+    }
+
+    if github_names.as_deref().map(str::trim) == Some("-") {
+        discord::util::update_guild_config(ctx, guild_id, |c| {
+            c.github_teams.remove(&team_slug);
+            Ok(())
+        })
+        .await?;
+
+        let reply = format!(":white_check_mark: Forgot the team '{}'", team_slug);
+        ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+            .await?;
+        return Ok(());
+    }
+
+    let members: Vec<model::GithubUserName> = match &github_names {
+        Some(names) => names
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(model::GithubUserName::from_str)
+            .collect(),
+        None => {
+            let org = match github::team_org(&team) {
+                Some(org) => org,
+                None => {
+                    let cfg_guard = ctx.data().cfg.lock().await;
+                    match cfg_guard
+                        .guilds
+                        .get(&guild_id)
+                        .and_then(|g| g.repos.first())
+                    {
+                        Some(repo) => repo.owner.clone(),
+                        None => String::new(),
+                    }
+                }
+            };
+            if org.is_empty() {
+                return Err("Use `/fizz setup` to choose a repository first".into());
+            }
+            match ctx.data().github.get_team_members(&org, &team_slug).await {
+                Ok(members) => members,
+                Err(e) => {
+                    return Err(DiscordError::new(
+                        format!("Unable to get members of team '{}' from Github", team_slug),
+                        e,
+                    ));
+                }
+            }
+        }
+    };
+
+    let mut reply = format!(
+        ":white_check_mark: The team '{}' now has {} members",
+        team_slug,
+        members.len()
+    );
+    for n in &members {
+        reply.push_str(&format!("\n* {}", n));
+    }
+
+    discord::util::update_guild_config(ctx, guild_id, |c| {
+        c.github_teams.insert(team_slug, members);
+        Ok(())
+    })
+    .await?;
+
+    ctx.send(poise::CreateReply::default().content(reply).ephemeral(true))
+        .await?;
+    Ok(())
+}
@@ -4,6 +4,8 @@
 
 use chrono::{NaiveDate, NaiveTime};
 
+use super::my_reports_are_sent_to::format_delivery;
+use super::my_search_is::format_cadence;
 use crate::discord::{DiscordContext, DiscordError};
 use crate::model;
 
@@ -19,17 +21,33 @@ pub async fn whoami(ctx: DiscordContext<'_>) -> Result<(), DiscordError> {
     let mut my_report_times: Vec<NaiveTime> = Vec::new();
     let mut my_away_until: Option<NaiveDate> = None;
     let mut my_lead: bool = false;
+    let mut my_triager: bool = false;
+    let mut my_author_report: bool = false;
+    let mut my_author_report_stale_days: u32 = 0;
+    let mut my_review_overdue_days: u32 = 0;
+    let mut my_watched_paths: Vec<String> = Vec::new();
+    let mut my_saved_searches: Vec<model::SavedSearch> = Vec::new();
+    let mut my_hide_failing_ci: bool = false;
+    let mut my_delivery = model::Delivery::default();
 
     {
         let cfg_guard = ctx.data().cfg.lock().await;
         if let Some(guild_config) = cfg_guard.guilds.get(&guild_id) {
             if let Some(user_config) = guild_config.users.get(&user_id) {
-                my_github_names = user_config.github_names.iter().cloned().collect();
-                my_timezone = user_config.timezone.clone();
+                my_github_names = user_config.github_names.to_vec();
+                my_timezone = user_config.timezone;
                 my_workdays = user_config.workdays.clone();
                 my_report_times = user_config.report_times.clone();
-                my_away_until = user_config.away_until.clone();
+                my_away_until = user_config.away_until;
                 my_lead = user_config.lead;
+                my_triager = user_config.triager;
+                my_author_report = user_config.author_report;
+                my_author_report_stale_days = user_config.author_report_stale_days;
+                my_review_overdue_days = user_config.review_overdue_days;
+                my_watched_paths = user_config.watched_paths.clone();
+                my_saved_searches = user_config.saved_searches.clone();
+                my_hide_failing_ci = user_config.hide_failing_ci;
+                my_delivery = user_config.delivery;
             }
         }
     }
@@ -50,6 +68,52 @@ pub async fn whoami(ctx: DiscordContext<'_>) -> Result<(), DiscordError> {
         reply.push_str("* You are not a lead and won't get pinged for leads issues\n");
     }
 
+    if my_triager {
+        reply.push_str("* You are a triager and your reports include PRs that have no reviewer\n");
+    } else {
+        reply.push_str(
+            "* You are not a triager and your reports don't include PRs that have no reviewer\n",
+        );
+    }
+
+    if my_author_report {
+        reply.push_str(&format!(
+            "* Your reports include your own PRs, calling out those waiting for review more than {} days\n",
+            my_author_report_stale_days
+        ));
+    } else {
+        reply.push_str("* Your reports do not include your own PRs\n");
+    }
+
+    reply.push_str(&format!(
+        "* Reviews requested from you are marked as overdue after {} days\n",
+        my_review_overdue_days
+    ));
+
+    for path in my_watched_paths {
+        reply.push_str(&format!("* You hear about PRs that change '{}'\n", path));
+    }
+
+    for search in my_saved_searches {
+        reply.push_str(&format!(
+            "* Your reports include the search '{}' for `{}`, {}\n",
+            search.name,
+            search.query,
+            format_cadence(search.cadence)
+        ));
+    }
+
+    if my_hide_failing_ci {
+        reply.push_str("* PRs with failing CI are left out of your reports\n");
+    } else {
+        reply.push_str("* PRs with failing CI are included in your reports\n");
+    }
+
+    reply.push_str(&format!(
+        "* Your reports are sent to {}\n",
+        format_delivery(my_delivery)
+    ));
+
     reply.push_str(&format!("* Your timezone is {}\n", my_timezone));
     for (num, name) in [
         ("0", "Sunday"),
@@ -15,7 +15,7 @@ pub async fn whois_everyone(ctx: DiscordContext<'_>) -> Result<(), DiscordError>
     let guild_id: model::DiscordGuildId = ctx.guild_id().unwrap().into();
 
     let mut any = false;
-    let mut reply = format!(":wave: Hello, here's all the registered Github users:\n");
+    let mut reply = ":wave: Hello, here's all the registered Github users:\n".to_string();
 
     {
         let cfg_guard = ctx.data().cfg.lock().await;
@@ -2,18 +2,24 @@
 // Exceptions. See /LICENSE for license information.
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 
+use std::sync::Arc;
+
 use tokio::sync::Mutex;
 
+use crate::github;
 use crate::model;
 
 pub struct DiscordData {
     pub cfg: Mutex<model::Config>,
+    /// Used for all access to Github.
+    pub github: Arc<dyn github::GithubSource>,
 }
 
 impl DiscordData {
-    pub fn new(cfg: model::Config) -> Self {
+    pub fn new(cfg: model::Config, github: Arc<dyn github::GithubSource>) -> Self {
         Self {
             cfg: Mutex::new(cfg),
+            github,
         }
     }
 }
@@ -58,7 +58,7 @@ async fn on_error<'a>(framework_error: poise::FrameworkError<'a, Arc<DiscordData
                 "ERROR: Running command `{}`{}: {}",
                 ctx.command().name,
                 friendly_name
-                    .and_then(|n| Some(format!(" for {}", n)))
+                    .map(|n| format!(" for {}", n))
                     .unwrap_or_default(),
                 error.reply.as_deref().unwrap_or(":no_entry: <no reply>"),
             );
@@ -69,17 +69,13 @@ async fn on_error<'a>(framework_error: poise::FrameworkError<'a, Arc<DiscordData
             // Send the error's reply back to the user.
             if let Some(reply) = error.reply {
                 // Errors dropped here.
-                match ctx
+                let _ = ctx
                     .send(
                         poise::CreateReply::default()
                             .content(format!(":no_entry: {}", reply))
                             .ephemeral(true),
                     )
-                    .await
-                {
-                    Ok(_) => {}
-                    Err(_) => {}
-                }
+                    .await;
             }
         }
 
@@ -147,10 +143,11 @@ pub async fn run(data: Arc<DiscordData>) -> Result<(), error::Error> {
 
     tokio::spawn(set_manager(client.shard_manager.clone()));
     tokio::spawn(tasks::watch_github(client.http.clone(), data.clone()));
+    tokio::spawn(tasks::receive_webhooks(data.clone()));
 
     match client.start().await {
         Ok(()) => Ok(()),
-        Err(e) => return Err(error::Error::DiscordConnectFailed(e)),
+        Err(e) => Err(error::Error::DiscordConnectFailed(Box::new(e))),
     }
 }
 
@@ -2,6 +2,8 @@
 // Exceptions. See /LICENSE for license information.
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 
+pub mod receive_webhooks;
 pub mod watch_github;
 
+pub use receive_webhooks::*;
 pub use watch_github::*;
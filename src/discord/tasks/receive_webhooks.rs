@@ -0,0 +1,248 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+
+use crate::discord::DiscordData;
+use crate::error::Error;
+use crate::github;
+use crate::model;
+
+/// Github sends payloads of up to 25MB, but the events that are listened to
+/// are far smaller than that.
+const MAX_PAYLOAD_BYTES: usize = 5 * 1024 * 1024;
+
+/// Listens for Github webhooks if `FIZZ_WEBHOOK_ADDR` is set, on that address.
+/// Webhooks must be signed with the secret in `FIZZ_WEBHOOK_SECRET`.
+pub async fn receive_webhooks(data: Arc<DiscordData>) {
+    let Ok(addr) = std::env::var("FIZZ_WEBHOOK_ADDR") else {
+        return;
+    };
+    let secret = match std::env::var("FIZZ_WEBHOOK_SECRET") {
+        Ok(secret) if !secret.is_empty() => secret,
+        _ => {
+            let e = Error::WebhookSecretMissing("FIZZ_WEBHOOK_SECRET".to_string());
+            eprintln!("ERROR: not receiving webhooks, {}", e);
+            return;
+        }
+    };
+    let listener = match TcpListener::bind(&addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            eprintln!("ERROR: not receiving webhooks, {}: {}", e, addr);
+            return;
+        }
+    };
+    println!("Receiving Github webhooks on {}", addr);
+    serve_webhooks(listener, Arc::new(secret.into_bytes()), data).await
+}
+
+async fn serve_webhooks(listener: TcpListener, secret: Arc<Vec<u8>>, data: Arc<DiscordData>) {
+    loop {
+        let stream = match listener.accept().await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                eprintln!("ERROR: accepting webhook connection {}", e);
+                continue;
+            }
+        };
+        let secret = secret.clone();
+        let data = data.clone();
+        tokio::spawn(async move {
+            let service =
+                hyper::service::service_fn(|req| handle_webhook(req, secret.clone(), data.clone()));
+            if let Err(e) = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                eprintln!("ERROR: serving webhook connection {}", e);
+            }
+        });
+    }
+}
+
+fn respond(status: StatusCode) -> Result<Response<Full<Bytes>>, Infallible> {
+    let mut response = Response::new(Full::default());
+    *response.status_mut() = status;
+    Ok(response)
+}
+
+async fn handle_webhook(
+    req: Request<Incoming>,
+    secret: Arc<Vec<u8>>,
+    data: Arc<DiscordData>,
+) -> Result<Response<Full<Bytes>>, Infallible> {
+    if req.method() != Method::POST {
+        return respond(StatusCode::METHOD_NOT_ALLOWED);
+    }
+    let header = |name| -> String {
+        req.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let event = header("x-github-event");
+    let signature = header("x-hub-signature-256");
+
+    let body = match Limited::new(req.into_body(), MAX_PAYLOAD_BYTES)
+        .collect()
+        .await
+    {
+        Ok(body) => body.to_bytes(),
+        Err(_) => return respond(StatusCode::PAYLOAD_TOO_LARGE),
+    };
+    if !github::verify_webhook_signature(&secret, &body, &signature) {
+        return respond(StatusCode::UNAUTHORIZED);
+    }
+
+    let change = match github::parse_webhook(&event, &body) {
+        Ok(Some(change)) => change,
+        Ok(None) => return respond(StatusCode::NO_CONTENT),
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            return respond(StatusCode::BAD_REQUEST);
+        }
+    };
+
+    // The cached lists are keyed by the repository as each guild configured
+    // it, which may not match the case in the payload.
+    let watched: Vec<model::RepoConfig> = {
+        let cfg_guard = data.cfg.lock().await;
+        cfg_guard
+            .guilds
+            .values()
+            .flat_map(|guild_config| &guild_config.repos)
+            .filter(|repo| repo.is(&change.repo_owner, &change.repo_name))
+            .cloned()
+            .collect()
+    };
+    if watched.is_empty() {
+        return respond(StatusCode::NO_CONTENT);
+    }
+
+    // The PR or issue in the payload takes the place of the cached one. When
+    // the payload doesn't carry all of it, the lists are fetched again instead.
+    for repo in &watched {
+        match &change.item {
+            Some(item) => data.github.apply_change(&repo.owner, &repo.name, item),
+            None => data.github.repo_changed(&repo.owner, &repo.name),
+        }
+    }
+    super::watch_github_github_changed().await;
+    respond(StatusCode::ACCEPTED)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ring::hmac;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    const SECRET: &[u8] = b"webhook secret";
+
+    fn sign(body: &[u8]) -> String {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, SECRET);
+        let tag = hmac::sign(&key, body);
+        let hex: String = tag.as_ref().iter().map(|b| format!("{:02x}", b)).collect();
+        format!("sha256={}", hex)
+    }
+
+    /// Starts listening on a free port on localhost, watching the repository
+    /// in the recorded payloads.
+    async fn start() -> std::net::SocketAddr {
+        let github = github::FakeGithub::load(Path::new(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/testdata/fake_github.json"
+        )))
+        .unwrap();
+        let mut cfg = model::new();
+        cfg.guilds.insert(
+            model::DiscordGuildId("1".to_string()),
+            model::GuildConfig {
+                repos: vec![model::RepoConfig {
+                    owner: "carbon-language".to_string(),
+                    name: "carbon-lang".to_string(),
+                }],
+                ..Default::default()
+            },
+        );
+        let data = Arc::new(DiscordData::new(cfg, Arc::new(github)));
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_webhooks(listener, Arc::new(SECRET.to_vec()), data));
+        addr
+    }
+
+    /// Posts a webhook, and returns the HTTP status line of the response.
+    async fn post(addr: std::net::SocketAddr, event: &str, body: &str, signature: &str) -> String {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST /webhook HTTP/1.1\r\nHost: localhost\r\nX-GitHub-Event: {}\r\n\
+             X-Hub-Signature-256: {}\r\nContent-Type: application/json\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            event,
+            signature,
+            body.len(),
+            body
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response.lines().next().unwrap_or_default().to_string()
+    }
+
+    #[tokio::test]
+    async fn recorded_payloads() {
+        let addr = start().await;
+        for event in ["pull_request", "pull_request_review", "issues"] {
+            let path = format!(
+                "{}/testdata/webhooks/{}.json",
+                env!("CARGO_MANIFEST_DIR"),
+                event
+            );
+            let body = std::fs::read_to_string(path).unwrap();
+            assert_eq!(
+                post(addr, event, &body, &sign(body.as_bytes())).await,
+                "HTTP/1.1 202 Accepted",
+                "{}",
+                event
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn rejected_payloads() {
+        let addr = start().await;
+        let body = r#"{"repository": {"name": "other", "owner": {"login": "someone"}}}"#;
+        assert_eq!(
+            post(addr, "pull_request", body, "sha256=00").await,
+            "HTTP/1.1 401 Unauthorized"
+        );
+        // Not a watched repository.
+        assert_eq!(
+            post(addr, "pull_request", body, &sign(body.as_bytes())).await,
+            "HTTP/1.1 204 No Content"
+        );
+        assert_eq!(
+            post(addr, "ping", "{}", &sign(b"{}")).await,
+            "HTTP/1.1 204 No Content"
+        );
+        assert_eq!(
+            post(addr, "issues", "{}", &sign(b"{}")).await,
+            "HTTP/1.1 400 Bad Request"
+        );
+    }
+}
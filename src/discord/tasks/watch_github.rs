@@ -2,10 +2,14 @@
 // Exceptions. See /LICENSE for license information.
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
+use futures::TryStreamExt;
 use poise::serenity_prelude as serenity;
+use serde::Serialize;
 use tokio::sync::Mutex;
 
 use crate::discord;
@@ -15,65 +19,211 @@ use crate::model;
 
 const WAKE_UP_FREQ_SECONDS: u64 = 60 * 5;
 
-static CANCEL_SLEEP: Mutex<Option<tokio::sync::mpsc::Sender<model::DiscordGuildId>>> =
-    Mutex::const_new(None);
+const PR_HEADER: &str = ":notepad_spiral: PRs for review ";
+const UPDATED_PR_HEADER: &str = ":arrows_counterclockwise: PRs updated since your review ";
+const AUTHORED_PR_HEADER: &str = ":pencil: Your open PRs ";
+const BLOCKING_ISSUES_HEADER: &str = ":fire_engine: Open leads issues (blocking) ";
+const UNASSIGNED_PR_HEADER: &str = ":inbox_tray: PRs without a reviewer ";
+const OWNED_PR_HEADER: &str = ":compass: Unassigned PRs in your area ";
+const WATCHED_PR_HEADER: &str = ":telescope: PRs in your areas ";
+const URGENT_ISSUES_HEADER: &str = ":rotating_light: New urgent leads issues ";
+const NONURGENT_ISSUES_HEADER: &str = ":chipmunk: Open leads issues (non-blocking) ";
+// The guild's searches use a different emoji than users' searches, so that
+// neither header is a prefix of the other.
+const USER_SEARCH_HEADER: &str = ":mag: ";
+const GUILD_SEARCH_HEADER: &str = ":mag_right: ";
+const ESCALATION_HEADER: &str = ":sos: PRs waiting too long for review";
+
+/// The start of every report section that is replaced by later reports.
+const REPORT_HEADERS: &[&str] = &[
+    PR_HEADER,
+    UPDATED_PR_HEADER,
+    AUTHORED_PR_HEADER,
+    BLOCKING_ISSUES_HEADER,
+    UNASSIGNED_PR_HEADER,
+    OWNED_PR_HEADER,
+    WATCHED_PR_HEADER,
+    URGENT_ISSUES_HEADER,
+    NONURGENT_ISSUES_HEADER,
+    USER_SEARCH_HEADER,
+    GUILD_SEARCH_HEADER,
+];
+
+/// Why `watch_github` was woken up before its next update period.
+enum Wake {
+    /// Report to everyone in the guild now, regardless of their report times.
+    ReportNow(model::DiscordGuildId),
+    /// Github announced a change, so look again for urgent issues to announce.
+    GithubChanged,
+}
+
+static CANCEL_SLEEP: Mutex<Option<tokio::sync::mpsc::Sender<Wake>>> = Mutex::const_new(None);
+
+/// Set while a `Wake::GithubChanged` is waiting to be received, so that a
+/// burst of changes only wakes `watch_github` once.
+static GITHUB_CHANGED_PENDING: AtomicBool = AtomicBool::new(false);
 
 pub async fn watch_github(http: Arc<serenity::Http>, data: Arc<DiscordData>) {
     let mut interval: tokio::time::Interval =
         tokio::time::interval(std::time::Duration::from_secs(WAKE_UP_FREQ_SECONDS));
 
-    // A channel for `report()` to wake this task up before `interval`, asking
-    // for an immediate report in `filter_guild_id`.
+    // A channel to wake this task up before `interval`, asking for an
+    // immediate report in the guilds of `report_now_guild_ids`, or for a look
+    // at Github changes.
     let (send_cancel_sleep, mut recv_cancel_sleep) = tokio::sync::mpsc::channel(100);
     CANCEL_SLEEP.lock().await.replace(send_cancel_sleep);
 
     let mut last_report_timestamp = Utc::now();
 
+    // The guilds that asked for a report now. They are kept until a report
+    // succeeds, so that asking while Github is paused or failing isn't lost.
+    let mut report_now_guild_ids: Vec<model::DiscordGuildId> = Vec::new();
+
     loop {
         // Wait for the next update period.
-        let mut filter_guild_id = None;
+        let mut changes_only = false;
         tokio::select! {
-            guild_id = recv_cancel_sleep.recv() => {
-                filter_guild_id = Some(guild_id.expect("CANCEL_SLEEP was closed"))
+            wake = recv_cancel_sleep.recv() => {
+                match wake.expect("CANCEL_SLEEP was closed") {
+                    Wake::ReportNow(guild_id) => {
+                        if !report_now_guild_ids.contains(&guild_id) {
+                            report_now_guild_ids.push(guild_id);
+                        }
+                    }
+                    Wake::GithubChanged => {
+                        // Changes from here on need another wake up.
+                        GITHUB_CHANGED_PENDING.store(false, Ordering::SeqCst);
+                        changes_only = true;
+                    }
+                }
             }
             _ = interval.tick() => {
             }
         }
 
         let now = Utc::now();
+        // A report asked for earlier still has to be sent in full.
+        let changes_only = changes_only && report_now_guild_ids.is_empty();
+
+        data.github.prune_caches();
+
+        // Github refuses requests until the quota is replenished, so don't
+        // poll before then. Reports that come due in the meantime are sent
+        // once polling resumes, as `last_report_timestamp` stays put.
+        if let Some(until) = data.github.rate_limit().exhausted_until(now) {
+            eprintln!(
+                "WARNING: Github rate limit exceeded, pausing until {}",
+                until
+            );
+            continue;
+        }
 
         let run_result = report_alerts(
             http.clone(),
             data.clone(),
             &last_report_timestamp,
             &now,
-            std::mem::replace(&mut filter_guild_id, None),
+            &report_now_guild_ids,
+            changes_only,
         )
         .await;
         match run_result {
+            // Reports that come due before the next update period are left
+            // for it.
+            Ok(()) if changes_only => {}
             Ok(()) => {
                 last_report_timestamp = now;
+                report_now_guild_ids.clear();
             }
             Err(e) => {
                 eprintln!("ERROR: watching github {}", e);
-                // Wait a bit and try again.
-                tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                // If the rate limit was exceeded, retrying only makes it
+                // worse, and the check above waits for it instead.
+                if data
+                    .github
+                    .rate_limit()
+                    .exhausted_until(Utc::now())
+                    .is_none()
+                {
+                    // Wait a bit and try again.
+                    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+                }
                 continue;
             }
         }
     }
 }
 
+/// Gets the PRs from all of the guild's repositories, matched up with the
+/// guild's users. Across repositories, the least recently updated come first,
+/// as they do within each repository.
+async fn get_prs(
+    github: Arc<dyn github::GithubSource>,
+    guild_config: &model::GuildConfig,
+) -> Result<Vec<github::Pr>, DiscordError> {
+    let mut prs = Vec::new();
+    for repo in &guild_config.repos {
+        let prs_state = github::get_prs(github.clone(), repo).await?;
+        let repo_prs: Vec<_> = github::filter_prs_for_guild(prs_state, guild_config)
+            .try_collect()
+            .await?;
+        prs.extend(repo_prs);
+    }
+    if guild_config.repos.len() > 1 {
+        prs.sort_by_key(|pr| pr.github_pr.updated_at);
+    }
+    Ok(prs)
+}
+
+/// Gets the leads issues from all of the guild's repositories, like `get_prs`.
+async fn get_issues(
+    github: Arc<dyn github::GithubSource>,
+    guild_config: &model::GuildConfig,
+) -> Result<Vec<github::LeadsIssue>, DiscordError> {
+    let mut issues = Vec::new();
+    for repo in &guild_config.repos {
+        let issues_state =
+            github::get_leads_issues(github.clone(), repo, &guild_config.issue_labels).await?;
+        let repo_issues: Vec<_> = github::filter_leads_issues_for_guild(issues_state, guild_config)
+            .try_collect()
+            .await?;
+        issues.extend(repo_issues);
+    }
+    if guild_config.repos.len() > 1 {
+        issues.sort_by_key(|issue| issue.github_issue.updated_at);
+    }
+    Ok(issues)
+}
+
+/// Whether escalations should be looked for. They are counted in workdays, so
+/// looking once an hour is plenty, and saves looking up the details of every
+/// PR when no report is due.
+fn escalations_due(
+    guild_config: &model::GuildConfig,
+    last_report_timestamp: &DateTime<Utc>,
+    now: &DateTime<Utc>,
+    ignore_time: bool,
+) -> bool {
+    let hour = |t: &DateTime<Utc>| t.timestamp().div_euclid(60 * 60);
+    (guild_config.escalation.after_workdays.is_some() || !guild_config.escalated_prs.is_empty())
+        && (ignore_time || hour(last_report_timestamp) < hour(now))
+}
+
 async fn report_alerts(
     http: Arc<serenity::Http>,
     data: Arc<DiscordData>,
     last_report_timestamp: &DateTime<Utc>,
     now: &DateTime<Utc>,
-    ignore_time_for_guild_id: Option<model::DiscordGuildId>,
+    ignore_time_for_guild_ids: &[model::DiscordGuildId],
+    // Only send what a change on Github can make due right away, which is the
+    // announcements of urgent issues. Everything else waits for its time.
+    changes_only: bool,
 ) -> Result<(), DiscordError> {
     struct GuildAlerts {
+        discord_guild_id: model::DiscordGuildId,
         discord_channel_id: model::DiscordChannelId,
-        discord_user_ids: Vec<model::DiscordUserId>,
+        tag_repos: RepoTags,
+        users: Vec<(model::DiscordUserId, model::UserConfig)>,
         prs: Arc<Vec<github::Pr>>,
         issues: Arc<Vec<github::LeadsIssue>>,
     }
@@ -82,68 +232,235 @@ async fn report_alerts(
     struct GuildWeeklyAlerts {
         discord_guild_id: model::DiscordGuildId,
         discord_channel_id: model::DiscordChannelId,
+        tag_repos: RepoTags,
         discord_user_ids: Vec<model::DiscordUserId>,
         issues: Arc<Vec<github::LeadsIssue>>,
     }
     let mut weekly_alerts = Vec::new();
 
-    let guild_ignores_time = |guild_id| match &ignore_time_for_guild_id {
-        Some(ignored_guild_id) => ignored_guild_id == guild_id,
-        None => false,
-    };
+    struct GuildAnnouncements {
+        discord_guild_id: model::DiscordGuildId,
+        discord_channel_id: model::DiscordChannelId,
+        tag_repos: RepoTags,
+        discord_user_ids: Vec<model::DiscordUserId>,
+        /// The immediately urgent issues that haven't been announced yet.
+        new_issue_keys: Vec<String>,
+        /// All the immediately urgent issues, which are announced after this.
+        issue_keys: Vec<String>,
+        issues: Arc<Vec<github::LeadsIssue>>,
+    }
+    let mut announcements = Vec::new();
+
+    struct GuildEscalations {
+        discord_guild_id: model::DiscordGuildId,
+        discord_channel_id: model::DiscordChannelId,
+        report_format: model::ReportFormat,
+        section: ReportSection,
+        /// The escalated PRs, including the ones escalated by `section`.
+        escalated_prs: HashMap<String, model::Escalated>,
+    }
+    let mut escalations = Vec::new();
+
+    struct GuildSearches {
+        discord_guild_id: model::DiscordGuildId,
+        discord_channel_id: model::DiscordChannelId,
+        tag_repos: RepoTags,
+        searches: Vec<SearchReport>,
+    }
+    let mut guild_searches = Vec::new();
+
+    // The report channels that may still have reports from before fizz kept
+    // track of them, with the messages that it did keep track of.
+    let mut sweeps = Vec::new();
+
+    let guild_ignores_time = |guild_id| ignore_time_for_guild_ids.contains(guild_id);
 
     {
-        let cfg_guard = data.cfg.lock().await;
-        for (guild_id, guild_config) in &cfg_guard.guilds {
+        let mut cfg_guard = data.cfg.lock().await;
+        for (guild_id, guild_config) in cfg_guard.guilds.iter_mut() {
             if guild_config.report_channel_id.is_empty() {
                 continue;
             }
 
-            // This is an `await` while we have a mutex guard on `data.cfg`, but
-            // its okay because we don't give access to `data` here. The future
-            // waits on Github access only.
-            let prs_state =
-                github::get_prs(&guild_config.repo_owner, &guild_config.repo_name).await?;
-            let issues_state =
-                github::get_leads_issues(&guild_config.repo_owner, &guild_config.repo_name).await?;
-
-            let mut discord_user_ids_to_alert: Vec<model::DiscordUserId> = Vec::new();
+            if !guild_config.legacy_reports_swept {
+                let tracked: Vec<model::DiscordMessageId> = guild_config
+                    .report_messages
+                    .values()
+                    .flat_map(|posted| &posted.messages)
+                    .map(|message| message.message_id.clone())
+                    .collect();
+                sweeps.push((
+                    guild_id.clone(),
+                    guild_config.report_channel_id.clone(),
+                    tracked,
+                ));
+            }
+
+            let mut users_to_alert: Vec<(model::DiscordUserId, model::UserConfig)> = Vec::new();
             let mut discord_user_ids_to_weekly_alert: Vec<model::DiscordUserId> = Vec::new();
-            for (discord_user_id, _) in &guild_config.users {
-                let user_alerts = model::discord_user_report_times(guild_config, discord_user_id);
+            if !changes_only {
+                for (discord_user_id, user_config) in &guild_config.users {
+                    let user_alerts =
+                        model::discord_user_report_times(guild_config, discord_user_id);
 
-                if guild_ignores_time(guild_id) {
-                    discord_user_ids_to_alert.push(discord_user_id.clone());
-                    discord_user_ids_to_weekly_alert.push(discord_user_id.clone());
-                } else {
-                    // Look for any alert times that we have passed since the last report attempt.
-                    let should_report =
-                        |report_time| last_report_timestamp < report_time && now >= report_time;
-                    if user_alerts.iter().any(should_report) {
-                        discord_user_ids_to_alert.push(discord_user_id.clone());
-
-                        if model::discord_user_weekly_report_needed(guild_config, discord_user_id) {
-                            discord_user_ids_to_weekly_alert.push(discord_user_id.clone());
+                    if guild_ignores_time(guild_id) {
+                        users_to_alert.push((discord_user_id.clone(), user_config.clone()));
+                        discord_user_ids_to_weekly_alert.push(discord_user_id.clone());
+                    } else {
+                        // Look for any alert times that we have passed since the last report attempt.
+                        let should_report =
+                            |report_time| last_report_timestamp < report_time && now >= report_time;
+                        if user_alerts.iter().any(should_report) {
+                            users_to_alert.push((discord_user_id.clone(), user_config.clone()));
+
+                            if model::discord_user_weekly_report_needed(
+                                guild_config,
+                                discord_user_id,
+                            ) {
+                                discord_user_ids_to_weekly_alert.push(discord_user_id.clone());
+                            }
                         }
                     }
                 }
             }
 
-            let prs: Arc<Vec<_>> =
-                Arc::new(github::filter_prs_for_guild(prs_state, guild_config).collect());
-            let issues: Arc<Vec<_>> = Arc::new(
-                github::filter_leads_issues_for_guild(issues_state, guild_config).collect(),
-            );
+            // Looking up the PRs takes several requests for each of them, so
+            // it's only done when a report or escalation could use them. The
+            // issues are cheap to look at again, and announcements of urgent
+            // ones can't wait for a report time.
+            //
+            // This is an `await` while we have a mutex guard on `data.cfg`, but
+            // its okay because we don't give access to `data.cfg` here. The
+            // future waits on Github access only.
+            let check_escalations = !changes_only
+                && escalations_due(
+                    guild_config,
+                    last_report_timestamp,
+                    now,
+                    guild_ignores_time(guild_id),
+                );
+            let prs = if !users_to_alert.is_empty() || check_escalations {
+                let prs = get_prs(data.github.clone(), guild_config).await?;
+                record_reviewed_prs(guild_config, &prs);
+                prs
+            } else {
+                Vec::new()
+            };
+            let issues = get_issues(data.github.clone(), guild_config).await?;
+
+            let prs = Arc::new(prs);
+            let issues = Arc::new(issues);
+            let tag_repos = RepoTags::for_repos(&guild_config.repos);
+
+            // Leads hear about immediately urgent issues as soon as they are
+            // seen, rather than at their report times.
+            let issue_keys: Vec<String> = issues
+                .iter()
+                .filter(|issue| issue.urgency == model::Urgency::Immediate)
+                .map(|issue| issue.key.clone())
+                .collect();
+            if issue_keys != guild_config.announced_issues {
+                let new_issue_keys = issue_keys
+                    .iter()
+                    .filter(|key| !guild_config.announced_issues.contains(key))
+                    .cloned()
+                    .collect();
+                let discord_user_ids = guild_config
+                    .users
+                    .iter()
+                    .filter(|(discord_user_id, user_config)| {
+                        user_config.lead
+                            && !model::discord_user_is_away(guild_config, discord_user_id)
+                    })
+                    .map(|(discord_user_id, _)| discord_user_id.clone())
+                    .collect();
+                announcements.push(GuildAnnouncements {
+                    discord_guild_id: guild_id.clone(),
+                    discord_channel_id: guild_config.report_channel_id.clone(),
+                    tag_repos,
+                    discord_user_ids,
+                    new_issue_keys,
+                    issue_keys,
+                    issues: issues.clone(),
+                });
+            }
+
+            if check_escalations {
+                let (section, escalated_prs) =
+                    render_escalations(&prs, guild_config, tag_repos, *now);
+                let discord_channel_id = if guild_config.escalation.channel_id.is_empty() {
+                    guild_config.report_channel_id.clone()
+                } else {
+                    guild_config.escalation.channel_id.clone()
+                };
+                escalations.push(GuildEscalations {
+                    discord_guild_id: guild_id.clone(),
+                    discord_channel_id,
+                    report_format: guild_config.report_format,
+                    section,
+                    escalated_prs,
+                });
+            }
+
+            // Saved searches for the guild are due on their own schedule,
+            // and users' saved searches are due at their report times.
+            let mut searches = Vec::new();
+            let mut due_searches: Vec<(Option<&model::DiscordUserId>, &model::SavedSearch)> =
+                Vec::new();
+            for saved_search in &guild_config.saved_searches {
+                due_searches.push((None, saved_search));
+            }
+            for (discord_user_id, user_config) in &users_to_alert {
+                for saved_search in &user_config.saved_searches {
+                    due_searches.push((Some(discord_user_id), saved_search));
+                }
+            }
+            due_searches.retain(|(_, saved_search)| {
+                !changes_only && (guild_ignores_time(guild_id) || saved_search.is_due(*now))
+            });
+            for (discord_user_id, saved_search) in due_searches {
+                let results = match github::search(
+                    data.github.clone(),
+                    &saved_search.query,
+                    &guild_config.repos,
+                )
+                .await
+                {
+                    Ok(results) => results,
+                    Err(e) if e.is_github_rate_limited() => return Err(e.into()),
+                    Err(e) => {
+                        // A bad query shouldn't hold up everything else.
+                        eprintln!("ERROR: saved search '{}' {}", saved_search.name, e);
+                        continue;
+                    }
+                };
+                searches.push(SearchReport {
+                    discord_user_id: discord_user_id.cloned(),
+                    name: saved_search.name.clone(),
+                    results,
+                });
+            }
+            if !searches.is_empty() {
+                guild_searches.push(GuildSearches {
+                    discord_guild_id: guild_id.clone(),
+                    discord_channel_id: guild_config.report_channel_id.clone(),
+                    tag_repos,
+                    searches,
+                });
+            }
 
             alerts.push(GuildAlerts {
+                discord_guild_id: guild_id.clone(),
                 discord_channel_id: guild_config.report_channel_id.clone(),
-                discord_user_ids: discord_user_ids_to_alert,
+                tag_repos,
+                users: users_to_alert,
                 prs,
                 issues: issues.clone(),
             });
             weekly_alerts.push(GuildWeeklyAlerts {
                 discord_guild_id: guild_id.clone(),
                 discord_channel_id: guild_config.report_channel_id.clone(),
+                tag_repos,
                 discord_user_ids: discord_user_ids_to_weekly_alert,
                 issues,
             });
@@ -151,12 +468,119 @@ async fn report_alerts(
     }
     // Drop the mutex guard before doing any `await` to avoid blocking other tasks.
 
+    for (discord_guild_id, discord_channel_id, tracked) in sweeps {
+        sweep_legacy_reports(http.clone(), discord_channel_id, tracked).await?;
+
+        let mut cfg_guard = data.cfg.lock().await;
+        if let Some(guild_config) = cfg_guard.guilds.get_mut(&discord_guild_id) {
+            guild_config.legacy_reports_swept = true;
+        }
+    }
+
+    for announcement in announcements {
+        if !announcement.new_issue_keys.is_empty() {
+            for discord_user_id in &announcement.discord_user_ids {
+                let sections = render_announcement_for_user(
+                    &announcement.issues,
+                    &announcement.new_issue_keys,
+                    announcement.tag_repos,
+                    discord_user_id,
+                );
+                send_user_report(
+                    http.clone(),
+                    &data,
+                    &announcement.discord_guild_id,
+                    announcement.discord_channel_id.clone(),
+                    discord_user_id,
+                    sections,
+                    false,
+                )
+                .await?;
+            }
+        }
+
+        let mut cfg_guard = data.cfg.lock().await;
+        if let Some(guild_config) = cfg_guard.guilds.get_mut(&announcement.discord_guild_id) {
+            guild_config.announced_issues = announcement.issue_keys;
+        }
+    }
+
+    for escalation in escalations {
+        // Escalations are repeated as new messages, rather than replacing the
+        // earlier ones, so that they don't go unnoticed.
+        for msg in escalation.section.messages_in(escalation.report_format) {
+            post_message(&http, &escalation.discord_channel_id, msg).await?;
+        }
+
+        let mut cfg_guard = data.cfg.lock().await;
+        if let Some(guild_config) = cfg_guard.guilds.get_mut(&escalation.discord_guild_id) {
+            guild_config.escalated_prs = escalation.escalated_prs;
+        }
+    }
+
+    for guild_search in guild_searches {
+        for search in &guild_search.searches {
+            let sections = render_search_report(search, guild_search.tag_repos);
+            match &search.discord_user_id {
+                Some(discord_user_id) => {
+                    send_user_report(
+                        http.clone(),
+                        &data,
+                        &guild_search.discord_guild_id,
+                        guild_search.discord_channel_id.clone(),
+                        discord_user_id,
+                        sections,
+                        false,
+                    )
+                    .await?
+                }
+                None => {
+                    let destination = ReportDestination {
+                        discord_channel_id: guild_search.discord_channel_id.clone(),
+                        discord_user_id: None,
+                        dm: false,
+                    };
+                    send_report(
+                        http.clone(),
+                        &data,
+                        &guild_search.discord_guild_id,
+                        &destination,
+                        sections,
+                        false,
+                    )
+                    .await?
+                }
+            }
+        }
+
+        let mut cfg_guard = data.cfg.lock().await;
+        if let Some(guild_config) = cfg_guard.guilds.get_mut(&guild_search.discord_guild_id) {
+            for search in guild_search.searches {
+                let saved_searches = match &search.discord_user_id {
+                    Some(discord_user_id) => match guild_config.users.get_mut(discord_user_id) {
+                        Some(user_config) => &mut user_config.saved_searches,
+                        None => continue,
+                    },
+                    None => &mut guild_config.saved_searches,
+                };
+                for saved_search in saved_searches.iter_mut() {
+                    if saved_search.name == search.name {
+                        saved_search.last_reported_at = Some(*now);
+                    }
+                }
+            }
+        }
+    }
+
     for alert in weekly_alerts {
         for discord_user_id in &alert.discord_user_ids {
             report_weekly_alerts_for_user(
                 http.clone(),
+                &data,
+                &alert.discord_guild_id,
                 alert.issues.clone(),
                 alert.discord_channel_id.clone(),
+                alert.tag_repos,
                 discord_user_id.clone(),
             )
             .await?;
@@ -176,25 +600,53 @@ async fn report_alerts(
     discord::util::save_config(&data).await?;
 
     for alert in alerts {
-        for discord_user_id in alert.discord_user_ids {
-            report_alerts_for_user(
+        for (discord_user_id, user_config) in alert.users {
+            let sections = render_report_for_user(
+                &alert.prs,
+                &alert.issues,
+                alert.tag_repos,
+                &discord_user_id,
+                &user_config,
+            );
+            send_user_report(
                 http.clone(),
-                alert.prs.clone(),
-                alert.issues.clone(),
+                &data,
+                &alert.discord_guild_id,
                 alert.discord_channel_id.clone(),
-                discord_user_id,
+                &discord_user_id,
+                sections,
+                user_config.ping_next_report,
             )
             .await?;
+
+            let mut cfg_guard = data.cfg.lock().await;
+            if let Some(guild_config) = cfg_guard.guilds.get_mut(&alert.discord_guild_id) {
+                if let Some(user_config) = guild_config.users.get_mut(&discord_user_id) {
+                    user_config.ping_next_report = false;
+                }
+            }
         }
     }
+    discord::util::save_config(&data).await?;
 
     Ok(())
 }
 
-async fn delete_messages_with_prefix(
+/// Whether a message from fizz is a report section, which later reports
+/// replace.
+fn is_report(content: &str) -> bool {
+    REPORT_HEADERS
+        .iter()
+        .any(|header| content.starts_with(header))
+}
+
+/// Deletes the reports that fizz posted in the channel before it kept track
+/// of them in `report_messages`. Other messages from fizz, like escalations,
+/// which are never replaced, are kept.
+async fn sweep_legacy_reports(
     http: Arc<serenity::Http>,
     discord_channel_id: model::DiscordChannelId,
-    prefix: String,
+    tracked: Vec<model::DiscordMessageId>,
 ) -> Result<(), DiscordError> {
     let user = http.get_current_user().await?;
     discord::util::delete_messages(http, discord_channel_id, |m| {
@@ -207,165 +659,2193 @@ async fn delete_messages_with_prefix(
             return false;
         }
 
-        m.content.starts_with(&prefix)
+        !tracked.contains(&m.id.into()) && is_report(&m.content)
     })
     .await
 }
 
-fn format_pr(pr: &github::Pr) -> String {
-    let mut msg: String = String::new();
+fn format_ci(ci: github::CiStatus) -> &'static str {
+    match ci {
+        github::CiStatus::Unknown => "",
+        github::CiStatus::Passing => " :green_circle:",
+        github::CiStatus::Failing => " :red_circle:",
+        github::CiStatus::Pending => " :yellow_circle:",
+    }
+}
+
+/// How reports say which repository each PR and issue is from.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum RepoTags {
+    /// The guild watches a single repository, so reports don't say.
+    Off,
+    /// Reports give the repository's name.
+    Names,
+    /// Reports give the repository's owner and name, because some watched
+    /// repositories have the same name.
+    FullNames,
+}
+
+impl RepoTags {
+    fn for_repos(repos: &[model::RepoConfig]) -> Self {
+        if repos.len() <= 1 {
+            return RepoTags::Off;
+        }
+        let mut names: Vec<String> = repos.iter().map(|r| r.name.to_lowercase()).collect();
+        names.sort();
+        names.dedup();
+        if names.len() < repos.len() {
+            RepoTags::FullNames
+        } else {
+            RepoTags::Names
+        }
+    }
+}
+
+/// The repository at the start of a line, when reporting on more than one.
+fn format_repo(repo: &model::RepoConfig, tag_repos: RepoTags) -> String {
+    match tag_repos {
+        RepoTags::Off => String::new(),
+        RepoTags::Names => format!("`{}` ", repo.name),
+        RepoTags::FullNames => format!("`{}` ", repo),
+    }
+}
+
+/// A PR or issue title for a report, with unbalanced formatting characters
+/// closed so they don't run into the rest of the report.
+fn format_title(title: &str) -> String {
+    let mut title = title.to_string();
+    if title.chars().filter(|c| *c == '`').count() % 2 == 1 {
+        title.push('`');
+    }
+    title
+}
+
+/// How long something has been waiting, like `4d` or `5h`, or `None` if it's
+/// been less than an hour.
+fn format_wait(since: DateTime<Utc>) -> Option<String> {
+    let waited = Utc::now() - since;
+    if waited.num_days() > 0 {
+        Some(format!("{}d", waited.num_days()))
+    } else if waited.num_hours() > 0 {
+        Some(format!("{}h", waited.num_hours()))
+    } else {
+        None
+    }
+}
+
+fn format_pr(
+    pr: &github::Pr,
+    discord_user_id: &model::DiscordUserId,
+    overdue_days: u32,
+    tag_repos: RepoTags,
+) -> String {
+    let mut msg: String = format_repo(&pr.repo, tag_repos);
     msg.push_str(&format!("[PR #{}](<{}>)", pr.github_pr.number, pr.url));
+    msg.push_str(format_ci(pr.ci));
     if let Some(user) = &pr.github_pr.user {
         msg.push_str(&format!(" **{}**", user.login));
     }
+    for detail in review_details(pr, discord_user_id, overdue_days) {
+        msg.push_str(&format!(" {}", detail));
+    }
+    if let Some(title) = &pr.github_pr.title {
+        msg.push_str(&format!("\n    {}", format_title(title)));
+    }
+    msg
+}
+
+/// How long the user's review of a PR has been waiting, and whether it only
+/// reaches them through a team.
+fn review_details(
+    pr: &github::Pr,
+    discord_user_id: &model::DiscordUserId,
+    overdue_days: u32,
+) -> Vec<String> {
+    let mut details = Vec::new();
+    if let Some(requested_at) = pr.review_requested_at_for(discord_user_id) {
+        if let Some(wait) = format_wait(requested_at) {
+            if (Utc::now() - requested_at).num_days() >= i64::from(overdue_days) {
+                details.push(format!(":alarm_clock: waiting {} (overdue)", wait));
+            } else {
+                details.push(format!("waiting {}", wait));
+            }
+        }
+    }
+    // Mark reviews that only reach the user through a team.
+    let user_reviewers = pr
+        .reviewers
+        .iter()
+        .filter(|r| r.discord_users.contains(discord_user_id));
+    let mut teams = Vec::new();
+    for r in user_reviewers {
+        match &r.team {
+            Some(team) => teams.push(team.as_str()),
+            None => {
+                teams.clear();
+                break;
+            }
+        }
+    }
+    if !teams.is_empty() {
+        teams.dedup();
+        details.push(format!("(via team {})", teams.join(", ")));
+    }
+    details
+}
+
+fn format_authored_pr(pr: &github::Pr, stale_days: u32, tag_repos: RepoTags) -> String {
+    let mut msg: String = format_repo(&pr.repo, tag_repos);
+    msg.push_str(&format!("[PR #{}](<{}>)", pr.github_pr.number, pr.url));
+    msg.push_str(format_ci(pr.ci));
+    msg.push_str(&format!(" {}", author_status(pr, stale_days)));
     if let Some(title) = &pr.github_pr.title {
-        msg.push_str(&format!("\n    {}", title));
-        // Close unbalanced formatting characters.
-        if title.chars().filter(|c| *c == '`').count() % 2 == 1 {
-            msg.push('`');
+        msg.push_str(&format!("\n    {}", format_title(title)));
+    }
+    msg
+}
+
+/// Where the author's PR stands in review.
+fn author_status(pr: &github::Pr, stale_days: u32) -> String {
+    let status = pr.author_status();
+    let mut msg = match status {
+        github::AuthorStatus::Approved => ":white_check_mark: approved",
+        github::AuthorStatus::ChangesRequested => ":construction: changes requested",
+        github::AuthorStatus::NoReviewer => ":grey_question: no reviewer assigned",
+        github::AuthorStatus::AwaitingReview => ":hourglass: waiting for review",
+    }
+    .to_string();
+    // Call out PRs that have been waiting on others for too long.
+    if status == github::AuthorStatus::AwaitingReview || status == github::AuthorStatus::NoReviewer
+    {
+        if let Some(since) = pr.waiting_since() {
+            let days = (Utc::now() - since).num_days();
+            if days > i64::from(stale_days) {
+                msg.push_str(&format!(" for {} days", days));
+            }
         }
     }
     msg
 }
 
-fn format_issue(issue: &github::LeadsIssue) -> String {
-    let mut msg = String::new();
+fn format_unassigned_pr(pr: &github::Pr, tag_repos: RepoTags) -> String {
+    let mut msg: String = format_repo(&pr.repo, tag_repos);
+    msg.push_str(&format!("[PR #{}](<{}>)", pr.github_pr.number, pr.url));
+    msg.push_str(format_ci(pr.ci));
+    if let Some(user) = &pr.github_pr.user {
+        msg.push_str(&format!(" **{}**", user.login));
+    }
+    if let Some(wait) = pr.github_pr.created_at.and_then(format_wait) {
+        msg.push_str(&format!(" opened {} ago", wait));
+    }
+    if let Some(title) = &pr.github_pr.title {
+        msg.push_str(&format!("\n    {}", format_title(title)));
+    }
+    msg
+}
+
+fn format_watched_pr(pr: &github::Pr, tag_repos: RepoTags) -> String {
+    let mut msg: String = format_repo(&pr.repo, tag_repos);
+    msg.push_str(&format!("[PR #{}](<{}>)", pr.github_pr.number, pr.url));
+    msg.push_str(format_ci(pr.ci));
+    if let Some(user) = &pr.github_pr.user {
+        msg.push_str(&format!(" **{}**", user.login));
+    }
+    if let Some(title) = &pr.github_pr.title {
+        msg.push_str(&format!("\n    {}", format_title(title)));
+    }
+    msg
+}
+
+fn format_issue(issue: &github::LeadsIssue, tag_repos: RepoTags) -> String {
+    let mut msg = format_repo(&issue.repo, tag_repos);
 
-    let title = &issue.github_issue.title;
     msg.push_str(&format!(
         "[Issue #{}](<{}>) {}",
-        issue.github_issue.number, issue.url, title
+        issue.github_issue.number,
+        issue.url,
+        format_title(&issue.github_issue.title)
     ));
-    // Close unbalanced formatting characters.
-    if title.chars().filter(|c| *c == '`').count() % 2 == 1 {
-        msg.push('`');
-    }
     msg
 }
 
-/// Send one or more messages, with each message capped at 2000 bytes (discord's limit).
-async fn generate_alert_messages<'a, T, ToString: Fn(&'a T) -> String>(
-    http: Arc<serenity::Http>,
+/// The messages in one section of a report to a user.
+#[derive(Clone)]
+struct ReportSection {
+    /// The start of each message in the section. Used to find the messages
+    /// from an earlier report.
     header: String,
-    alerts: Vec<&'a T>,
-    to_string: ToString,
-    discord_channel_id: model::DiscordChannelId,
-) -> Result<(), DiscordError> {
-    let header_len = header.len();
-
-    let mut i = 0;
-    while i < alerts.len() {
-        let mut msg = String::new();
-        let mut msg_len = 0;
-        loop {
-            let Some(alert) = alerts.get(i) else {
-                break;
-            };
-            let line = &format!("\n* {}", to_string(alert));
-            if header_len + msg_len + line.len() > 2000 {
-                break;
-            }
-            msg.push_str(line);
-            msg_len += line.len();
-            i += 1;
-        }
+    messages: Vec<String>,
+    /// The same section as embeds, for guilds that use `ReportFormat::Embeds`.
+    embeds: Vec<ReportEmbed>,
+    /// What the section lists, to tell whether it has anything new.
+    keys: Vec<String>,
+}
 
-        if !msg.is_empty() {
-            let msg = serenity::CreateMessage::new().content(format!("{}{}", header, msg));
-            http.send_message(discord_channel_id.clone().into(), vec![], &msg)
-                .await?;
-        }
-    }
+/// A field of an embed, for one item in a report section.
+#[derive(Clone, Serialize, PartialEq, Debug)]
+struct EmbedField {
+    name: String,
+    value: String,
+}
 
-    Ok(())
+/// An embed for a report section, or for part of one if the section doesn't
+/// fit in a single embed.
+#[derive(Clone, Serialize, PartialEq, Debug)]
+struct ReportEmbed {
+    color: u32,
+    fields: Vec<EmbedField>,
 }
 
-async fn report_alerts_for_user(
-    http: Arc<serenity::Http>,
-    prs: Arc<Vec<github::Pr>>,
-    issues: Arc<Vec<github::LeadsIssue>>,
-    discord_channel_id: model::DiscordChannelId,
-    discord_user_id: model::DiscordUserId,
-) -> Result<(), DiscordError> {
-    const PR_HEADER: &str = ":notepad_spiral: PRs for review ";
-    const BLOCKING_ISSUES_HEADER: &str = ":fire_engine: Open leads issues (blocking) ";
+/// A message in a report, in the guild's `ReportFormat`.
+#[derive(Clone, PartialEq, Debug)]
+struct ReportMessage {
+    content: String,
+    embed: Option<ReportEmbed>,
+}
 
-    let pr_header = format!("{}{}", PR_HEADER, discord_user_id);
-    let issue_header = format!("{}{}", BLOCKING_ISSUES_HEADER, discord_user_id);
+impl ReportMessage {
+    fn embed_json(&self) -> Option<String> {
+        self.embed
+            .as_ref()
+            .map(|embed| serde_json::to_string(embed).unwrap())
+    }
 
-    delete_messages_with_prefix(http.clone(), discord_channel_id.clone(), pr_header.clone())
-        .await?;
-    delete_messages_with_prefix(
-        http.clone(),
-        discord_channel_id.clone(),
-        issue_header.clone(),
-    )
-    .await?;
+    fn create_embeds(&self) -> Vec<serenity::CreateEmbed> {
+        match &self.embed {
+            Some(embed) => vec![serenity::CreateEmbed::new().colour(embed.color).fields(
+                embed
+                    .fields
+                    .iter()
+                    .map(|field| (field.name.clone(), field.value.clone(), false)),
+            )],
+            None => Vec::new(),
+        }
+    }
+}
 
-    let user_prs: Vec<_> = prs
-        .iter()
-        .filter(|pr| {
-            pr.reviewers
+impl ReportSection {
+    fn messages_in(&self, format: model::ReportFormat) -> Vec<ReportMessage> {
+        match format {
+            model::ReportFormat::Text => self
+                .messages
                 .iter()
-                .any(|r| r.discord_users.contains(&discord_user_id))
-        })
-        .collect();
-    let user_issues: Vec<_> = issues
-        .iter()
-        .filter(|issue: &_| {
-            issue.urgency == github::Urgency::Blocked && issue.leads.contains(&discord_user_id)
-        })
-        .collect();
+                .map(|msg| ReportMessage {
+                    content: msg.clone(),
+                    embed: None,
+                })
+                .collect(),
+            // The header stays in the message's content, where it pings the
+            // user.
+            model::ReportFormat::Embeds => self
+                .embeds
+                .iter()
+                .map(|embed| ReportMessage {
+                    content: self.header.trim_end().to_string(),
+                    embed: Some(embed.clone()),
+                })
+                .collect(),
+        }
+    }
+}
 
-    generate_alert_messages(
-        http.clone(),
-        pr_header,
-        user_prs,
-        format_pr,
-        discord_channel_id.clone(),
-    )
-    .await?;
-    generate_alert_messages(
-        http,
-        issue_header,
-        user_issues,
-        format_issue,
-        discord_channel_id,
-    )
-    .await?;
-    Ok(())
+/// Something that is listed in a report section.
+trait ReportItem {
+    /// Identifies the item from one report to the next.
+    fn key(&self) -> &str;
+
+    /// The item as a field of an embed.
+    fn embed_field(&self) -> EmbedField;
+
+    /// How urgent the item is, for those that have an urgency. The most
+    /// urgent item picks the color of the section's embeds.
+    fn urgency(&self) -> Option<model::Urgency> {
+        None
+    }
 }
 
-async fn report_weekly_alerts_for_user(
-    http: Arc<serenity::Http>,
-    issues: Arc<Vec<github::LeadsIssue>>,
-    discord_channel_id: model::DiscordChannelId,
-    discord_user_id: model::DiscordUserId,
-) -> Result<(), DiscordError> {
-    const NONURGENT_ISSUES_HEADER: &str = ":chipmunk: Open leads issues (non-blocking) ";
+impl ReportItem for github::Pr {
+    fn key(&self) -> &str {
+        &self.key
+    }
 
-    let header = format!("{}{}", NONURGENT_ISSUES_HEADER, discord_user_id);
+    fn embed_field(&self) -> EmbedField {
+        pr_embed_field(self)
+    }
+}
 
-    delete_messages_with_prefix(http.clone(), discord_channel_id.clone(), header.clone()).await?;
+impl ReportItem for github::LeadsIssue {
+    fn key(&self) -> &str {
+        &self.key
+    }
 
-    let user_issues: Vec<_> = issues
-        .iter()
-        .filter(|issue: &_| {
-            issue.urgency == github::Urgency::Normal && issue.leads.contains(&discord_user_id)
-        })
-        .collect();
+    fn embed_field(&self) -> EmbedField {
+        issue_embed_field("Issue", &self.github_issue, &self.repo, &self.url)
+    }
 
-    generate_alert_messages(http, header, user_issues, format_issue, discord_channel_id).await?;
-    Ok(())
+    fn urgency(&self) -> Option<model::Urgency> {
+        Some(self.urgency)
+    }
+}
+
+impl ReportItem for github::SearchResult {
+    fn key(&self) -> &str {
+        &self.url
+    }
+
+    fn embed_field(&self) -> EmbedField {
+        let kind = if self.is_pr() { "PR" } else { "Issue" };
+        issue_embed_field(kind, &self.github_issue, &self.repo, &self.url)
+    }
+}
+
+impl ReportItem for StuckPr<'_> {
+    fn key(&self) -> &str {
+        &self.pr.key
+    }
+
+    fn embed_field(&self) -> EmbedField {
+        pr_embed_field(self.pr)
+    }
+
+    fn urgency(&self) -> Option<model::Urgency> {
+        Some(model::Urgency::Immediate)
+    }
+}
+
+// Discord's limits on embeds, in characters.
+const EMBED_FIELDS_LIMIT: usize = 25;
+const EMBED_FIELD_NAME_LIMIT: usize = 256;
+const EMBED_FIELD_VALUE_LIMIT: usize = 1024;
+const EMBED_TOTAL_LIMIT: usize = 6000;
+
+/// Cuts `s` down to `limit` characters, marking where it was cut.
+fn truncate(s: &str, limit: usize) -> String {
+    if s.chars().count() <= limit {
+        return s.to_string();
+    }
+    let mut truncated: String = s.chars().take(limit - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn embed_color(urgency: Option<model::Urgency>) -> u32 {
+    match urgency {
+        Some(model::Urgency::Immediate) => 0xED4245,
+        Some(model::Urgency::EveryReport) => 0xE67E22,
+        Some(model::Urgency::Weekly) => 0xFEE75C,
+        Some(model::Urgency::Never) => 0x99AAB5,
+        None => 0x5865F2,
+    }
+}
+
+fn format_labels<'a>(labels: impl Iterator<Item = &'a octocrab::models::Label>) -> Option<String> {
+    let labels: Vec<&str> = labels.map(|label| label.name.as_str()).collect();
+    if labels.is_empty() {
+        None
+    } else {
+        Some(format!("labels: {}", labels.join(", ")))
+    }
+}
+
+fn embed_field(name: String, details: Vec<String>) -> EmbedField {
+    EmbedField {
+        name: truncate(&name, EMBED_FIELD_NAME_LIMIT),
+        value: truncate(&details.join(" · "), EMBED_FIELD_VALUE_LIMIT),
+    }
+}
+
+fn pr_embed_field(pr: &github::Pr) -> EmbedField {
+    let number = pr.github_pr.number;
+    let mut details = vec![format!("[{}#{}](<{}>)", pr.repo.name, number, pr.url)];
+    if let Some(user) = &pr.github_pr.user {
+        details.push(format!("by **{}**", user.login));
+    }
+    if let Some(wait) = pr.github_pr.created_at.and_then(format_wait) {
+        details.push(format!("opened {} ago", wait));
+    }
+    details.extend(format_labels(pr.github_pr.labels.iter().flatten()));
+    let ci = match pr.ci {
+        github::CiStatus::Unknown => None,
+        github::CiStatus::Passing => Some("CI passing :green_circle:"),
+        github::CiStatus::Failing => Some("CI failing :red_circle:"),
+        github::CiStatus::Pending => Some("CI pending :yellow_circle:"),
+    };
+    details.extend(ci.map(str::to_string));
+    let name = match &pr.github_pr.title {
+        Some(title) => format!("PR #{}: {}", number, title),
+        None => format!("PR #{}", number),
+    };
+    embed_field(name, details)
+}
+
+fn issue_embed_field(
+    kind: &str,
+    issue: &octocrab::models::issues::Issue,
+    repo: &model::RepoConfig,
+    url: &str,
+) -> EmbedField {
+    let mut details = vec![format!("[{}#{}](<{}>)", repo.name, issue.number, url)];
+    details.push(format!("by **{}**", issue.user.login));
+    if let Some(wait) = format_wait(issue.created_at) {
+        details.push(format!("opened {} ago", wait));
+    }
+    details.extend(format_labels(issue.labels.iter()));
+    embed_field(
+        format!("{} #{}: {}", kind, issue.number, issue.title),
+        details,
+    )
+}
+
+/// Splits the fields of a section into as many embeds as it takes to stay in
+/// Discord's limits.
+fn render_embeds(header: &str, fields: Vec<EmbedField>, color: u32) -> Vec<ReportEmbed> {
+    let mut embeds = Vec::new();
+    let mut embed = ReportEmbed {
+        color,
+        fields: Vec::new(),
+    };
+    // The message's content doesn't count towards the limit, but leave room
+    // for it anyway in case Discord starts counting it.
+    let mut total = header.chars().count();
+    for field in fields {
+        let len = field.name.chars().count() + field.value.chars().count();
+        if embed.fields.len() == EMBED_FIELDS_LIMIT || total + len > EMBED_TOTAL_LIMIT {
+            embeds.push(std::mem::replace(
+                &mut embed,
+                ReportEmbed {
+                    color,
+                    fields: Vec::new(),
+                },
+            ));
+            total = header.chars().count();
+        }
+        total += len;
+        embed.fields.push(field);
+    }
+    if !embed.fields.is_empty() {
+        embeds.push(embed);
+    }
+    embeds
+}
+
+/// Render one or more messages, with each message capped at 2000 bytes (discord's limit).
+/// The alerts are rendered as embeds too, for guilds that prefer them.
+fn render_alert_messages<'a, T: ReportItem, ToString: Fn(&'a T) -> String>(
+    header: String,
+    alerts: Vec<&'a T>,
+    to_string: ToString,
+) -> ReportSection {
+    render_alert_messages_with_details(header, alerts, to_string, |_| Vec::new())
+}
+
+/// Like `render_alert_messages`, for sections that say more about each alert
+/// than its embed field does. `to_details` adds that to the field.
+fn render_alert_messages_with_details<
+    'a,
+    T: ReportItem,
+    ToString: Fn(&'a T) -> String,
+    ToDetails: Fn(&'a T) -> Vec<String>,
+>(
+    header: String,
+    alerts: Vec<&'a T>,
+    to_string: ToString,
+    to_details: ToDetails,
+) -> ReportSection {
+    let header_len = header.len();
+    let mut messages = Vec::new();
+    let keys = alerts.iter().map(|alert| alert.key().to_string()).collect();
+    let color = embed_color(alerts.iter().filter_map(|alert| alert.urgency()).min());
+    let fields = alerts
+        .iter()
+        .map(|alert| {
+            let mut field = alert.embed_field();
+            let details = to_details(alert);
+            if !details.is_empty() {
+                field.value = truncate(
+                    &format!("{} · {}", field.value, details.join(" · ")),
+                    EMBED_FIELD_VALUE_LIMIT,
+                );
+            }
+            field
+        })
+        .collect();
+    let embeds = render_embeds(&header, fields, color);
+
+    let mut i = 0;
+    while i < alerts.len() {
+        let mut msg = String::new();
+        let mut msg_len = 0;
+        while let Some(alert) = alerts.get(i) {
+            let line = &format!("\n* {}", to_string(alert));
+            if header_len + msg_len + line.len() > 2000 {
+                break;
+            }
+            msg.push_str(line);
+            msg_len += line.len();
+            i += 1;
+        }
+
+        if !msg.is_empty() {
+            messages.push(format!("{}{}", header, msg));
+        }
+    }
+
+    ReportSection {
+        header,
+        messages,
+        embeds,
+        keys,
+    }
+}
+
+/// A change to the messages of a section that was posted before.
+#[derive(PartialEq, Debug)]
+enum MessageUpdate {
+    Keep(model::PostedMessage),
+    Edit(model::DiscordMessageId, ReportMessage),
+    Post(ReportMessage),
+    Delete(model::DiscordMessageId),
+}
+
+/// How to turn the `posted` messages of a section into the new `section`.
+/// Sections that list something new are posted afresh, so that the user is
+/// pinged, as are all sections when `repost` is set. Otherwise the messages
+/// are edited in place.
+fn plan_section_update(
+    section: &ReportSection,
+    format: model::ReportFormat,
+    posted: &model::PostedSection,
+    repost: bool,
+) -> Vec<MessageUpdate> {
+    let messages = section.messages_in(format);
+    let has_new_keys = section.keys.iter().any(|key| !posted.keys.contains(key));
+    if repost || has_new_keys {
+        let deletes = posted
+            .messages
+            .iter()
+            .map(|posted| MessageUpdate::Delete(posted.message_id.clone()));
+        let posts = messages.into_iter().map(MessageUpdate::Post);
+        return deletes.chain(posts).collect();
+    }
+
+    let mut updates = Vec::new();
+    for (i, msg) in messages.iter().enumerate() {
+        updates.push(match posted.messages.get(i) {
+            Some(posted) if posted.content == msg.content && posted.embed == msg.embed_json() => {
+                MessageUpdate::Keep(posted.clone())
+            }
+            Some(posted) => MessageUpdate::Edit(posted.message_id.clone(), msg.clone()),
+            None => MessageUpdate::Post(msg.clone()),
+        });
+    }
+    for posted in posted.messages.iter().skip(messages.len()) {
+        updates.push(MessageUpdate::Delete(posted.message_id.clone()));
+    }
+    updates
+}
+
+async fn post_message(
+    http: &serenity::Http,
+    discord_channel_id: &model::DiscordChannelId,
+    msg: ReportMessage,
+) -> Result<model::PostedMessage, DiscordError> {
+    let create = serenity::CreateMessage::new()
+        .content(&msg.content)
+        .embeds(msg.create_embeds());
+    let message = http
+        .send_message(discord_channel_id.into(), vec![], &create)
+        .await?;
+    Ok(model::PostedMessage {
+        message_id: message.id.into(),
+        embed: msg.embed_json(),
+        content: msg.content,
+    })
+}
+
+/// Where a report is sent.
+struct ReportDestination<'a> {
+    discord_channel_id: model::DiscordChannelId,
+    /// The user that the report is for, or `None` for the guild's reports.
+    discord_user_id: Option<&'a model::DiscordUserId>,
+    /// Whether the channel is the user's direct messages.
+    dm: bool,
+}
+
+/// The messages that were posted before to `destination`.
+fn posted_sections_mut<'a>(
+    guild_config: &'a mut model::GuildConfig,
+    destination: &ReportDestination,
+) -> Option<&'a mut HashMap<String, model::PostedSection>> {
+    if destination.dm {
+        let user_config = guild_config.users.get_mut(destination.discord_user_id?)?;
+        Some(&mut user_config.dm_report_messages)
+    } else {
+        Some(&mut guild_config.report_messages)
+    }
+}
+
+/// Replace the messages from any earlier report with the new `sections`,
+/// editing them in place where possible. `repost` posts every section afresh.
+async fn send_report(
+    http: Arc<serenity::Http>,
+    data: &DiscordData,
+    discord_guild_id: &model::DiscordGuildId,
+    destination: &ReportDestination<'_>,
+    sections: Vec<ReportSection>,
+    repost: bool,
+) -> Result<(), DiscordError> {
+    let discord_channel_id = &destination.discord_channel_id;
+    let format = {
+        let cfg_guard = data.cfg.lock().await;
+        match cfg_guard.guilds.get(discord_guild_id) {
+            Some(guild_config) => guild_config.report_format,
+            None => model::ReportFormat::default(),
+        }
+    };
+    for section in sections {
+        let posted = {
+            let mut cfg_guard = data.cfg.lock().await;
+            cfg_guard
+                .guilds
+                .get_mut(discord_guild_id)
+                .and_then(|guild_config| posted_sections_mut(guild_config, destination))
+                .and_then(|posted_sections| posted_sections.get(&section.header))
+                .cloned()
+        };
+        let mut posted = posted.unwrap_or_else(|| model::PostedSection {
+            channel_id: discord_channel_id.clone(),
+            discord_user_id: destination.discord_user_id.cloned(),
+            messages: Vec::new(),
+            keys: Vec::new(),
+        });
+        if posted.channel_id != *discord_channel_id {
+            // The section moved to another channel.
+            for message in std::mem::take(&mut posted.messages) {
+                discord::util::delete_message(&http, &posted.channel_id, &message.message_id)
+                    .await?;
+            }
+        }
+
+        let channel_id: serenity::ChannelId = discord_channel_id.into();
+        let mut messages = Vec::new();
+        for update in plan_section_update(&section, format, &posted, repost) {
+            match update {
+                MessageUpdate::Keep(message) => messages.push(message),
+                MessageUpdate::Edit(message_id, msg) => {
+                    // Setting the embeds, even to none, replaces any from before.
+                    let edit = serenity::EditMessage::new()
+                        .content(&msg.content)
+                        .embeds(msg.create_embeds());
+                    let result = http
+                        .edit_message(channel_id, (&message_id).into(), &edit, vec![])
+                        .await;
+                    match result {
+                        Ok(_) => messages.push(model::PostedMessage {
+                            message_id,
+                            embed: msg.embed_json(),
+                            content: msg.content,
+                        }),
+                        Err(e) if discord::util::is_not_found(&e) => {
+                            messages.push(post_message(&http, discord_channel_id, msg).await?)
+                        }
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+                MessageUpdate::Post(msg) => {
+                    messages.push(post_message(&http, discord_channel_id, msg).await?)
+                }
+                MessageUpdate::Delete(message_id) => {
+                    discord::util::delete_message(&http, discord_channel_id, &message_id).await?
+                }
+            }
+        }
+
+        let mut cfg_guard = data.cfg.lock().await;
+        let posted_sections = cfg_guard
+            .guilds
+            .get_mut(discord_guild_id)
+            .and_then(|guild_config| posted_sections_mut(guild_config, destination));
+        if let Some(posted_sections) = posted_sections {
+            if messages.is_empty() {
+                posted_sections.remove(&section.header);
+            } else {
+                posted_sections.insert(
+                    section.header,
+                    model::PostedSection {
+                        channel_id: discord_channel_id.clone(),
+                        discord_user_id: destination.discord_user_id.cloned(),
+                        messages,
+                        keys: section.keys,
+                    },
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+/// What to do with a user's thread before sending them a report.
+#[derive(PartialEq, Debug)]
+enum ThreadPlan {
+    /// Post in the report channel.
+    Channel,
+    /// Post in the report channel, after removing the user from their thread
+    /// and forgetting it.
+    Leave(model::DiscordChannelId),
+    /// Post in the user's thread.
+    Use(model::DiscordChannelId),
+    /// Post in a new thread, which the user is added to. The old thread, if
+    /// any, is deleted.
+    Create(Option<model::DiscordChannelId>),
+}
+
+/// Decides where a user's reports go in the guild's report channel. Threads
+/// are kept for as long as the guild uses the same report channel and kind of
+/// thread, without looking them up for every report.
+fn plan_thread(
+    report_threads: model::ReportThreads,
+    discord_channel_id: &model::DiscordChannelId,
+    thread: Option<&model::ReportThread>,
+) -> ThreadPlan {
+    match (report_threads, thread) {
+        (model::ReportThreads::Off, None) => ThreadPlan::Channel,
+        (model::ReportThreads::Off, Some(thread)) => ThreadPlan::Leave(thread.thread_id.clone()),
+        (kind, Some(thread)) if thread.parent_id == *discord_channel_id && thread.kind == kind => {
+            ThreadPlan::Use(thread.thread_id.clone())
+        }
+        (_, thread) => ThreadPlan::Create(thread.map(|thread| thread.thread_id.clone())),
+    }
+}
+
+async fn set_report_thread(
+    data: &DiscordData,
+    discord_guild_id: &model::DiscordGuildId,
+    discord_user_id: &model::DiscordUserId,
+    thread: Option<model::ReportThread>,
+) {
+    let mut cfg_guard = data.cfg.lock().await;
+    if let Some(user_config) = cfg_guard
+        .guilds
+        .get_mut(discord_guild_id)
+        .and_then(|guild_config| guild_config.users.get_mut(discord_user_id))
+    {
+        user_config.report_thread = thread;
+    }
+}
+
+/// Creates a thread in the report channel for a user's reports, and adds the
+/// user to it.
+async fn create_report_thread(
+    http: &serenity::Http,
+    discord_channel_id: &model::DiscordChannelId,
+    discord_user_id: &model::DiscordUserId,
+    kind: model::ReportThreads,
+    friendly_name: &str,
+) -> Result<model::ReportThread, DiscordError> {
+    let channel_type = match kind {
+        model::ReportThreads::Private => serenity::ChannelType::PrivateThread,
+        _ => serenity::ChannelType::PublicThread,
+    };
+    let name = if friendly_name.is_empty() {
+        "Your reports".to_string()
+    } else {
+        format!("Reports for {}", friendly_name)
+    };
+    let thread = serenity::CreateThread::new(name)
+        .kind(channel_type)
+        .auto_archive_duration(serenity::AutoArchiveDuration::OneWeek);
+    let thread = http
+        .create_thread(discord_channel_id.into(), &thread, None)
+        .await?;
+    http.add_thread_channel_member(thread.id, discord_user_id.into())
+        .await?;
+    Ok(model::ReportThread {
+        thread_id: thread.into(),
+        parent_id: discord_channel_id.clone(),
+        kind,
+    })
+}
+
+/// The channel for a user's reports in the guild: the report channel, or the
+/// user's thread in it when the guild gives each user a thread. Users that
+/// don't get reports in the guild, `in_channel`, leave their thread. When the
+/// thread can't be set up, the reports go to the report channel.
+async fn user_report_channel(
+    http: &serenity::Http,
+    data: &DiscordData,
+    discord_guild_id: &model::DiscordGuildId,
+    discord_channel_id: model::DiscordChannelId,
+    discord_user_id: &model::DiscordUserId,
+    in_channel: bool,
+) -> model::DiscordChannelId {
+    let (report_threads, thread, friendly_name) = {
+        let cfg_guard = data.cfg.lock().await;
+        let Some(guild_config) = cfg_guard.guilds.get(discord_guild_id) else {
+            return discord_channel_id;
+        };
+        let Some(user_config) = guild_config.users.get(discord_user_id) else {
+            return discord_channel_id;
+        };
+        (
+            guild_config.report_threads,
+            user_config.report_thread.clone(),
+            user_config.friendly_name.clone(),
+        )
+    };
+    let report_threads = if in_channel {
+        report_threads
+    } else {
+        model::ReportThreads::Off
+    };
+
+    match plan_thread(report_threads, &discord_channel_id, thread.as_ref()) {
+        ThreadPlan::Channel => discord_channel_id,
+        ThreadPlan::Leave(thread_id) => {
+            match http
+                .remove_thread_channel_member((&thread_id).into(), discord_user_id.into())
+                .await
+            {
+                Err(e) if !discord::util::is_not_found(&e) => {
+                    // Try again with the next report.
+                    eprintln!(
+                        "WARNING: unable to remove {} from their report thread: {}",
+                        discord_user_id, e
+                    );
+                }
+                _ => set_report_thread(data, discord_guild_id, discord_user_id, None).await,
+            }
+            discord_channel_id
+        }
+        ThreadPlan::Use(thread_id) => thread_id,
+        ThreadPlan::Create(old_thread_id) => {
+            // The guild changed its report channel or the kind of thread, so
+            // the old thread makes way for a new one.
+            if let Some(old_thread_id) = &old_thread_id {
+                if let Err(e) = http.delete_channel(old_thread_id.into(), None).await {
+                    if !discord::util::is_not_found(&e) {
+                        eprintln!(
+                            "WARNING: unable to delete the old report thread of {}: {}",
+                            discord_user_id, e
+                        );
+                    }
+                }
+            }
+            match create_report_thread(
+                http,
+                &discord_channel_id,
+                discord_user_id,
+                report_threads,
+                &friendly_name,
+            )
+            .await
+            {
+                Ok(thread) => {
+                    let thread_id = thread.thread_id.clone();
+                    set_report_thread(data, discord_guild_id, discord_user_id, Some(thread)).await;
+                    thread_id
+                }
+                Err(e) => {
+                    // Such as when fizz may not create private threads.
+                    eprintln!(
+                        "WARNING: unable to create a report thread for {}: {}",
+                        discord_user_id, e
+                    );
+                    set_report_thread(data, discord_guild_id, discord_user_id, None).await;
+                    discord_channel_id
+                }
+            }
+        }
+    }
+}
+
+/// Whether a Discord error is for something that no longer exists.
+fn is_gone(e: &DiscordError) -> bool {
+    e.details
+        .as_ref()
+        .and_then(|details| details.downcast_ref::<serenity::Error>())
+        .is_some_and(discord::util::is_not_found)
+}
+
+/// What a user's report does in one place it could be sent.
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum Placement {
+    Send,
+    /// Delete the earlier report.
+    Clear,
+    Skip,
+}
+
+/// Where a user's report is sent, and where the earlier one is deleted.
+#[derive(PartialEq, Debug)]
+struct DeliveryPlan {
+    dm: Placement,
+    channel: Placement,
+}
+
+/// Decides where a user's report goes. When their direct messages can't be
+/// reached, `dm_failed`, the report goes to the channel instead.
+fn plan_delivery(delivery: model::Delivery, has_dm_reports: bool, dm_failed: bool) -> DeliveryPlan {
+    let dm = if dm_failed {
+        Placement::Skip
+    } else if delivery.to_dm() {
+        Placement::Send
+    } else if has_dm_reports {
+        Placement::Clear
+    } else {
+        Placement::Skip
+    };
+    let channel = if delivery.to_channel() || (dm_failed && delivery.to_dm()) {
+        Placement::Send
+    } else {
+        Placement::Clear
+    };
+    DeliveryPlan { dm, channel }
+}
+
+/// Sends a report for a user to the report channel, to their direct
+/// messages, or to both, as they prefer. Where the report isn't sent, the
+/// earlier one is deleted.
+async fn send_user_report(
+    http: Arc<serenity::Http>,
+    data: &DiscordData,
+    discord_guild_id: &model::DiscordGuildId,
+    discord_channel_id: model::DiscordChannelId,
+    discord_user_id: &model::DiscordUserId,
+    sections: Vec<ReportSection>,
+    repost: bool,
+) -> Result<(), DiscordError> {
+    let (delivery, has_dm_reports) = {
+        let cfg_guard = data.cfg.lock().await;
+        match cfg_guard
+            .guilds
+            .get(discord_guild_id)
+            .and_then(|guild_config| guild_config.users.get(discord_user_id))
+        {
+            Some(user_config) => (
+                user_config.delivery,
+                !user_config.dm_report_messages.is_empty(),
+            ),
+            None => (model::Delivery::default(), false),
+        }
+    };
+    let cleared = |sections: &[ReportSection]| -> Vec<ReportSection> {
+        sections
+            .iter()
+            .map(|section| ReportSection {
+                header: section.header.clone(),
+                messages: Vec::new(),
+                embeds: Vec::new(),
+                keys: Vec::new(),
+            })
+            .collect()
+    };
+
+    let mut plan = plan_delivery(delivery, has_dm_reports, false);
+    if plan.dm != Placement::Skip {
+        let dm_sections = if plan.dm == Placement::Send {
+            sections.clone()
+        } else {
+            cleared(&sections)
+        };
+        let result: Result<(), DiscordError> = async {
+            let recipient = serde_json::json!({ "recipient_id": discord_user_id.0 });
+            let dm_channel = http.create_private_channel(&recipient).await?;
+            let destination = ReportDestination {
+                discord_channel_id: (&dm_channel).into(),
+                discord_user_id: Some(discord_user_id),
+                dm: true,
+            };
+            send_report(
+                http.clone(),
+                data,
+                discord_guild_id,
+                &destination,
+                dm_sections,
+                repost,
+            )
+            .await
+        }
+        .await;
+        // Users that closed their direct messages, or left the guild, still
+        // get their reports in the channel, and everyone else still gets
+        // theirs.
+        if let Err(e) = result {
+            eprintln!(
+                "WARNING: unable to send reports to {} in direct messages: {}",
+                discord_user_id, e
+            );
+            plan = plan_delivery(delivery, has_dm_reports, true);
+        }
+    }
+
+    let in_channel = plan.channel == Placement::Send;
+    let channel_sections = if in_channel {
+        sections
+    } else {
+        cleared(&sections)
+    };
+    let report_channel_id = discord_channel_id.clone();
+    let discord_channel_id = user_report_channel(
+        &http,
+        data,
+        discord_guild_id,
+        discord_channel_id,
+        discord_user_id,
+        in_channel,
+    )
+    .await;
+    let destination = ReportDestination {
+        discord_channel_id,
+        discord_user_id: Some(discord_user_id),
+        dm: false,
+    };
+    let result = send_report(
+        http.clone(),
+        data,
+        discord_guild_id,
+        &destination,
+        channel_sections.clone(),
+        repost,
+    )
+    .await;
+    match result {
+        // When the user's thread is gone, or can't be posted in, the report
+        // goes to the report channel. A new thread is made for the next one.
+        Err(e) if destination.discord_channel_id != report_channel_id => {
+            eprintln!(
+                "WARNING: unable to send reports to the thread of {}: {}",
+                discord_user_id, e
+            );
+            if is_gone(&e) {
+                set_report_thread(data, discord_guild_id, discord_user_id, None).await;
+            }
+            let destination = ReportDestination {
+                discord_channel_id: report_channel_id,
+                discord_user_id: Some(discord_user_id),
+                dm: false,
+            };
+            send_report(
+                http,
+                data,
+                discord_guild_id,
+                &destination,
+                channel_sections,
+                repost,
+            )
+            .await
+        }
+        result => result,
+    }
+}
+
+fn render_report_for_user(
+    prs: &[github::Pr],
+    issues: &[github::LeadsIssue],
+    tag_repos: RepoTags,
+    discord_user_id: &model::DiscordUserId,
+    user_config: &model::UserConfig,
+) -> Vec<ReportSection> {
+    // PRs that are waiting on their author are left out, as there's nothing
+    // for the reviewer to do yet. So are PRs with failing CI, if the user
+    // doesn't review those. The longest waiting PRs come first.
+    let user_prs_with_status = |status| -> Vec<_> {
+        let mut user_prs: Vec<_> = prs
+            .iter()
+            .filter(|pr| pr.review_status_for(discord_user_id) == Some(status))
+            .filter(|pr| !user_config.hide_failing_ci || pr.ci != github::CiStatus::Failing)
+            .collect();
+        user_prs.sort_by_key(|pr| {
+            let requested_at = pr.review_requested_at_for(discord_user_id);
+            (requested_at.is_none(), requested_at)
+        });
+        user_prs
+    };
+    let user_prs = user_prs_with_status(github::ReviewStatus::NewRequest);
+    let user_updated_prs = user_prs_with_status(github::ReviewStatus::AuthorUpdated);
+    let user_authored_prs: Vec<_> = if user_config.author_report {
+        prs.iter()
+            .filter(|pr| pr.authors.contains(discord_user_id))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let user_issues: Vec<_> = issues
+        .iter()
+        .filter(|issue: &_| {
+            issue.urgency <= model::Urgency::EveryReport && issue.leads.contains(discord_user_id)
+        })
+        .collect();
+    // Triagers find reviewers for PRs that have none, the oldest first.
+    let unassigned_prs: Vec<_> = if user_config.triager {
+        let mut unassigned_prs: Vec<_> = prs.iter().filter(|pr| pr.is_unassigned()).collect();
+        unassigned_prs.sort_by_key(|pr| pr.github_pr.created_at);
+        unassigned_prs
+    } else {
+        Vec::new()
+    };
+    // Owners of the changed files are likely reviewers for unassigned PRs.
+    let mut owned_prs: Vec<_> = prs
+        .iter()
+        .filter(|pr| pr.code_owners.contains(discord_user_id))
+        .collect();
+    owned_prs.sort_by_key(|pr| pr.github_pr.created_at);
+    let watched_prs: Vec<_> = prs
+        .iter()
+        .filter(|pr| pr.watchers.contains(discord_user_id))
+        .collect();
+
+    vec![
+        render_alert_messages_with_details(
+            format!("{}{}", PR_HEADER, discord_user_id),
+            user_prs,
+            |pr| {
+                format_pr(
+                    pr,
+                    discord_user_id,
+                    user_config.review_overdue_days,
+                    tag_repos,
+                )
+            },
+            |pr| review_details(pr, discord_user_id, user_config.review_overdue_days),
+        ),
+        render_alert_messages_with_details(
+            format!("{}{}", UPDATED_PR_HEADER, discord_user_id),
+            user_updated_prs,
+            |pr| {
+                format_pr(
+                    pr,
+                    discord_user_id,
+                    user_config.review_overdue_days,
+                    tag_repos,
+                )
+            },
+            |pr| review_details(pr, discord_user_id, user_config.review_overdue_days),
+        ),
+        render_alert_messages_with_details(
+            format!("{}{}", AUTHORED_PR_HEADER, discord_user_id),
+            user_authored_prs,
+            |pr| format_authored_pr(pr, user_config.author_report_stale_days, tag_repos),
+            |pr| vec![author_status(pr, user_config.author_report_stale_days)],
+        ),
+        render_alert_messages(
+            format!("{}{}", BLOCKING_ISSUES_HEADER, discord_user_id),
+            user_issues,
+            |issue| format_issue(issue, tag_repos),
+        ),
+        render_alert_messages(
+            format!("{}{}", UNASSIGNED_PR_HEADER, discord_user_id),
+            unassigned_prs,
+            |pr| format_unassigned_pr(pr, tag_repos),
+        ),
+        render_alert_messages(
+            format!("{}{}", OWNED_PR_HEADER, discord_user_id),
+            owned_prs,
+            |pr| format_unassigned_pr(pr, tag_repos),
+        ),
+        render_alert_messages(
+            format!("{}{}", WATCHED_PR_HEADER, discord_user_id),
+            watched_prs,
+            |pr| format_watched_pr(pr, tag_repos),
+        ),
+    ]
+}
+
+fn render_announcement_for_user(
+    issues: &[github::LeadsIssue],
+    issue_keys: &[String],
+    tag_repos: RepoTags,
+    discord_user_id: &model::DiscordUserId,
+) -> Vec<ReportSection> {
+    let user_issues: Vec<_> = issues
+        .iter()
+        .filter(|issue: &_| {
+            issue_keys.contains(&issue.key) && issue.leads.contains(discord_user_id)
+        })
+        .collect();
+
+    vec![render_alert_messages(
+        format!("{}{}", URGENT_ISSUES_HEADER, discord_user_id),
+        user_issues,
+        |issue| format_issue(issue, tag_repos),
+    )]
+}
+
+fn render_weekly_report_for_user(
+    issues: &[github::LeadsIssue],
+    tag_repos: RepoTags,
+    discord_user_id: &model::DiscordUserId,
+) -> Vec<ReportSection> {
+    let user_issues: Vec<_> = issues
+        .iter()
+        .filter(|issue: &_| {
+            // Leads that aren't assigned to or mentioned in an urgent issue
+            // hear about it here too.
+            match issue.urgency {
+                model::Urgency::Immediate | model::Urgency::EveryReport => {
+                    issue.other_leads.contains(discord_user_id)
+                }
+                model::Urgency::Weekly => {
+                    issue.leads.contains(discord_user_id)
+                        || issue.other_leads.contains(discord_user_id)
+                }
+                model::Urgency::Never => false,
+            }
+        })
+        .collect();
+
+    vec![render_alert_messages(
+        format!("{}{}", NONURGENT_ISSUES_HEADER, discord_user_id),
+        user_issues,
+        |issue| format_issue(issue, tag_repos),
+    )]
+}
+
+async fn report_weekly_alerts_for_user(
+    http: Arc<serenity::Http>,
+    data: &DiscordData,
+    discord_guild_id: &model::DiscordGuildId,
+    issues: Arc<Vec<github::LeadsIssue>>,
+    discord_channel_id: model::DiscordChannelId,
+    tag_repos: RepoTags,
+    discord_user_id: model::DiscordUserId,
+) -> Result<(), DiscordError> {
+    let sections = render_weekly_report_for_user(&issues, tag_repos, &discord_user_id);
+    send_user_report(
+        http,
+        data,
+        discord_guild_id,
+        discord_channel_id,
+        &discord_user_id,
+        sections,
+        false,
+    )
+    .await
+}
+
+/// The results of a saved search, for a user or the whole guild.
+struct SearchReport {
+    /// The user that saved the search, or `None` for the guild's searches.
+    discord_user_id: Option<model::DiscordUserId>,
+    name: String,
+    results: Vec<github::SearchResult>,
+}
+
+fn format_search_result(result: &github::SearchResult, tag_repos: RepoTags) -> String {
+    let mut msg = format_repo(&result.repo, tag_repos);
+
+    msg.push_str(&format!(
+        "[{} #{}](<{}>) {}",
+        if result.is_pr() { "PR" } else { "Issue" },
+        result.github_issue.number,
+        result.url,
+        format_title(&result.github_issue.title)
+    ));
+    msg
+}
+
+/// The header of the section for a saved search, of the user or of the guild.
+pub fn search_header(name: &str, discord_user_id: Option<&model::DiscordUserId>) -> String {
+    match discord_user_id {
+        Some(discord_user_id) => format!("{}**{}** {}", USER_SEARCH_HEADER, name, discord_user_id),
+        None => format!("{}**{}**", GUILD_SEARCH_HEADER, name),
+    }
+}
+
+fn render_search_report(search: &SearchReport, tag_repos: RepoTags) -> Vec<ReportSection> {
+    vec![render_alert_messages(
+        search_header(&search.name, search.discord_user_id.as_ref()),
+        search.results.iter().collect(),
+        |result| format_search_result(result, tag_repos),
+    )]
+}
+
+/// A PR with reviews that have been outstanding for longer than its guild
+/// allows.
+struct StuckPr<'a> {
+    pr: &'a github::Pr,
+    /// The reviewers that the PR is waiting on, with how many of their
+    /// workdays it has waited.
+    reviewers: Vec<(model::DiscordUserId, u32)>,
+}
+
+fn find_stuck_prs<'a>(
+    prs: &'a [github::Pr],
+    guild_config: &model::GuildConfig,
+    after_workdays: u32,
+    now: DateTime<Utc>,
+) -> Vec<StuckPr<'a>> {
+    let mut stuck_prs = Vec::new();
+    for pr in prs {
+        if pr.github_pr.draft.unwrap_or_default() {
+            continue;
+        }
+        let mut reviewers: Vec<(model::DiscordUserId, u32)> = Vec::new();
+        for r in &pr.reviewers {
+            let (Some(requested_at), false) = (
+                r.requested_at,
+                r.status == github::ReviewStatus::WaitingOnAuthor,
+            ) else {
+                continue;
+            };
+            for discord_user_id in &r.discord_users {
+                // The leads aren't told about reviewers who are away.
+                if model::discord_user_is_away(guild_config, discord_user_id) {
+                    continue;
+                }
+                let workdays = model::discord_user_workdays_since(
+                    guild_config,
+                    discord_user_id,
+                    requested_at,
+                    now,
+                );
+                if workdays <= after_workdays {
+                    continue;
+                }
+                // A user can be asked more than once, such as through a team.
+                match reviewers.iter_mut().find(|(id, _)| id == discord_user_id) {
+                    Some((_, max_workdays)) => *max_workdays = workdays.max(*max_workdays),
+                    None => reviewers.push((discord_user_id.clone(), workdays)),
+                }
+            }
+        }
+        if !reviewers.is_empty() {
+            stuck_prs.push(StuckPr { pr, reviewers });
+        }
+    }
+    stuck_prs
+}
+
+/// How long to wait before escalating a PR again, after it has been escalated
+/// `count` times. This starts at a day and doubles, up to a week.
+fn escalation_backoff(count: u32) -> Duration {
+    Duration::days(1 << count.saturating_sub(1).min(3)).min(Duration::days(7))
+}
+
+/// Who a stuck PR is waiting on, and for how long.
+fn escalation_details(stuck_pr: &StuckPr) -> String {
+    let reviewers: Vec<String> = stuck_pr
+        .reviewers
+        .iter()
+        .map(|(discord_user_id, workdays)| format!("{} ({} workdays)", discord_user_id, workdays))
+        .collect();
+    format!("waiting on {}", reviewers.join(", "))
+}
+
+fn format_escalation(stuck_pr: &StuckPr, tag_repos: RepoTags) -> String {
+    let pr = stuck_pr.pr;
+    let mut msg: String = format_repo(&pr.repo, tag_repos);
+    msg.push_str(&format!("[PR #{}](<{}>)", pr.github_pr.number, pr.url));
+    if let Some(user) = &pr.github_pr.user {
+        msg.push_str(&format!(" **{}**", user.login));
+    }
+    msg.push_str(&format!(" {}", escalation_details(stuck_pr)));
+    if let Some(title) = &pr.github_pr.title {
+        msg.push_str(&format!("\n    {}", format_title(title)));
+    }
+    msg
+}
+
+/// Remembers the PRs that each user has reviewed, and the commit they
+/// reviewed, so that they hear about new commits to them. PRs that are no
+/// longer open are forgotten.
+fn record_reviewed_prs(guild_config: &mut model::GuildConfig, prs: &[github::Pr]) {
+    for user_config in guild_config.users.values_mut() {
+        user_config
+            .reviewed_prs
+            .retain(|key, _| prs.iter().any(|pr| pr.key == *key));
+        for pr in prs {
+            let commit_id = user_config
+                .github_names
+                .iter()
+                .filter_map(|name| pr.last_reviews.get(name))
+                .max_by_key(|review| review.submitted_at)
+                .and_then(|review| review.commit_id.clone());
+            if let Some(commit_id) = commit_id {
+                user_config.reviewed_prs.insert(pr.key.clone(), commit_id);
+            }
+        }
+    }
+}
+
+/// Renders the escalation of the guild's PRs that have waited too long for
+/// review, leaving out those that were escalated too recently. Also returns
+/// the guild's escalated PRs, once this is sent.
+fn render_escalations(
+    prs: &[github::Pr],
+    guild_config: &model::GuildConfig,
+    tag_repos: RepoTags,
+    now: DateTime<Utc>,
+) -> (ReportSection, HashMap<String, model::Escalated>) {
+    let stuck_prs = match guild_config.escalation.after_workdays {
+        Some(after_workdays) => find_stuck_prs(prs, guild_config, after_workdays, now),
+        None => Vec::new(),
+    };
+
+    let mut escalated_prs = HashMap::new();
+    let mut due = Vec::new();
+    for stuck_pr in &stuck_prs {
+        let count = match guild_config.escalated_prs.get(&stuck_pr.pr.key) {
+            Some(escalated)
+                if now - escalated.last_escalated_at < escalation_backoff(escalated.count) =>
+            {
+                escalated_prs.insert(stuck_pr.pr.key.clone(), escalated.clone());
+                continue;
+            }
+            Some(escalated) => escalated.count + 1,
+            None => 1,
+        };
+        escalated_prs.insert(
+            stuck_pr.pr.key.clone(),
+            model::Escalated {
+                count,
+                last_escalated_at: now,
+            },
+        );
+        due.push(stuck_pr);
+    }
+
+    // Without a channel of their own, escalations go to the leads.
+    let mut header = ESCALATION_HEADER.to_string();
+    if guild_config.escalation.channel_id.is_empty() {
+        for (discord_user_id, user_config) in &guild_config.users {
+            if user_config.lead && !model::discord_user_is_away(guild_config, discord_user_id) {
+                header.push_str(&format!(" {}", discord_user_id));
+            }
+        }
+    }
+
+    let section = render_alert_messages_with_details(
+        header,
+        due,
+        |stuck_pr| format_escalation(stuck_pr, tag_repos),
+        |stuck_pr| vec![escalation_details(stuck_pr)],
+    );
+    (section, escalated_prs)
 }
 
 pub async fn watch_github_wake_now(guild_id: model::DiscordGuildId) -> Result<(), DiscordError> {
     let guard = CANCEL_SLEEP.lock().await;
     if let Some(sender) = guard.as_ref() {
-        match sender.send(guild_id).await {
+        match sender.send(Wake::ReportNow(guild_id)).await {
             Ok(()) => {}
             Err(e) => return Err(e.to_string().into()),
         }
     }
     Ok(())
 }
+
+/// Wakes up `watch_github` to announce any urgent issues in the latest changes
+/// from Github.
+pub async fn watch_github_github_changed() {
+    if GITHUB_CHANGED_PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let guard = CANCEL_SLEEP.lock().await;
+    if let Some(sender) = guard.as_ref() {
+        // The only error is from the channel closing, which happens when
+        // shutting down.
+        let _ = sender.send(Wake::GithubChanged).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    const FIXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/fake_github.json");
+
+    fn user(github_name: &str) -> model::UserConfig {
+        let mut user_config = model::UserConfig::new(github_name.to_string());
+        user_config
+            .github_names
+            .push(model::GithubUserName::from_str(github_name));
+        user_config
+    }
+
+    fn repo(name: &str) -> model::RepoConfig {
+        model::RepoConfig {
+            owner: "carbon-language".to_string(),
+            name: name.to_string(),
+        }
+    }
+
+    fn guild_config() -> model::GuildConfig {
+        let mut bob = user("bob");
+        bob.lead = true;
+        let mut alice = user("alice");
+        alice.author_report = true;
+
+        let mut cfg = model::GuildConfig {
+            repos: vec![repo("carbon-lang")],
+            ..Default::default()
+        };
+        cfg.github_teams.insert(
+            "toolchain".to_string(),
+            vec![
+                model::GithubUserName::from_str("bob"),
+                model::GithubUserName::from_str("dave"),
+            ],
+        );
+        cfg.users.insert(model::DiscordUserId("1".to_string()), bob);
+        cfg.users
+            .insert(model::DiscordUserId("2".to_string()), alice);
+        cfg
+    }
+
+    fn fake_github() -> Arc<dyn github::GithubSource> {
+        Arc::new(github::FakeGithub::load(Path::new(FIXTURE)).unwrap())
+    }
+
+    /// Loads the PRs and leads issues in the fixture for the guild.
+    fn load_fixture(cfg: &model::GuildConfig) -> (Vec<github::Pr>, Vec<github::LeadsIssue>) {
+        futures::executor::block_on(async {
+            (
+                get_prs(fake_github(), cfg).await.unwrap(),
+                get_issues(fake_github(), cfg).await.unwrap(),
+            )
+        })
+    }
+
+    /// Renders the report for the user, keeping only the sections with
+    /// messages.
+    fn render(cfg: &model::GuildConfig, discord_user_id: &str) -> Vec<String> {
+        let (prs, issues) = load_fixture(cfg);
+        let discord_user_id = model::DiscordUserId(discord_user_id.to_string());
+        let user_config = &cfg.users[&discord_user_id];
+        let tag_repos = RepoTags::for_repos(&cfg.repos);
+        render_report_for_user(&prs, &issues, tag_repos, &discord_user_id, user_config)
+            .into_iter()
+            .chain(render_weekly_report_for_user(
+                &issues,
+                tag_repos,
+                &discord_user_id,
+            ))
+            .flat_map(|section| section.messages)
+            .collect()
+    }
+
+    #[test]
+    fn reviewer_report() {
+        let messages = render(&guild_config(), "1");
+        assert_eq!(messages.len(), 4, "{:#?}", messages);
+
+        let new_requests = &messages[0];
+        assert!(new_requests.starts_with(":notepad_spiral: PRs for review <@1>"));
+        assert!(new_requests.contains("[PR #101]"));
+        assert!(new_requests.contains(":green_circle:"));
+        assert!(new_requests.contains("[PR #104]"));
+        assert!(new_requests.contains("(via team toolchain)"));
+        // Waiting on the author.
+        assert!(!new_requests.contains("[PR #103]"));
+        // Asked to look again after requesting changes.
+        assert!(new_requests.contains("[PR #109]"));
+
+        let updated = &messages[1];
+        assert!(updated.starts_with(":arrows_counterclockwise: PRs updated since your review <@1>"));
+        assert!(updated.contains("[PR #102]"));
+        // The unbalanced backtick in the title is closed.
+        assert!(updated.ends_with("Fix the `lexer`"));
+
+        assert!(messages[2].contains("[Issue #201]"));
+        assert!(!messages[2].contains("[Issue #202]"));
+        assert!(messages[3].contains("[Issue #202]"));
+        assert!(!messages.iter().any(|m| m.contains("#203")));
+    }
+
+    #[test]
+    fn reviews_show_how_long_they_waited() {
+        let mut cfg = guild_config();
+        let messages = render(&cfg, "1");
+        let new_requests = &messages[0];
+        // #104 was requested from bob's team before #101 was requested from
+        // bob.
+        let pr_104 = new_requests.find("[PR #104]").unwrap();
+        let pr_101 = new_requests.find("[PR #101]").unwrap();
+        assert!(pr_104 < pr_101, "{}", new_requests);
+        assert!(new_requests.contains(":alarm_clock: waiting "));
+
+        cfg.users
+            .get_mut(&model::DiscordUserId("1".to_string()))
+            .unwrap()
+            .review_overdue_days = 100_000;
+        let messages = render(&cfg, "1");
+        assert!(messages[0].contains(" waiting "));
+        assert!(!messages[0].contains(":alarm_clock:"));
+    }
+
+    #[test]
+    fn escalations() {
+        let mut cfg = guild_config();
+        cfg.escalation.after_workdays = Some(5);
+        let (prs, _) = load_fixture(&cfg);
+        let now = Utc::now();
+
+        let (section, escalated_prs) = render_escalations(&prs, &cfg, RepoTags::Off, now);
+        assert_eq!(section.messages.len(), 1);
+        let msg = &section.messages[0];
+        // Without an escalation channel, the leads are pinged.
+        assert!(msg.starts_with(":sos: PRs waiting too long for review <@1>\n"));
+        assert!(msg.contains("[PR #101]"));
+        assert!(msg.contains("[PR #102]"));
+        assert!(msg.contains("[PR #104]"));
+        assert!(msg.contains("waiting on <@1> ("));
+        // Waiting on the author.
+        assert!(!msg.contains("[PR #103]"));
+        assert_eq!(escalated_prs["carbon-language/carbon-lang#101"].count, 1);
+
+        // Escalations back off.
+        cfg.escalated_prs = escalated_prs;
+        let (section, escalated_prs) =
+            render_escalations(&prs, &cfg, RepoTags::Off, now + Duration::hours(12));
+        assert!(section.messages.is_empty());
+        assert_eq!(escalated_prs["carbon-language/carbon-lang#101"].count, 1);
+        let (section, escalated_prs) =
+            render_escalations(&prs, &cfg, RepoTags::Off, now + Duration::days(1));
+        assert_eq!(section.messages.len(), 1);
+        assert_eq!(escalated_prs["carbon-language/carbon-lang#101"].count, 2);
+
+        // Reviewers who are away aren't escalated.
+        let bob = model::DiscordUserId("1".to_string());
+        cfg.users.get_mut(&bob).unwrap().away_until = chrono::NaiveDate::from_ymd_opt(9999, 1, 1);
+        let (section, _) = render_escalations(&prs, &cfg, RepoTags::Off, now + Duration::days(3));
+        assert!(section.messages.is_empty());
+        cfg.users.get_mut(&bob).unwrap().away_until = None;
+
+        // PRs are no longer escalated once escalation is turned off.
+        cfg.escalation.after_workdays = None;
+        let (section, escalated_prs) = render_escalations(&prs, &cfg, RepoTags::Off, now);
+        assert!(section.messages.is_empty());
+        assert!(escalated_prs.is_empty());
+        // They are looked for once an hour.
+        cfg.escalation.after_workdays = Some(5);
+        let at = |t: &str| t.parse::<DateTime<Utc>>().unwrap();
+        let due = |last, now, ignore_time| escalations_due(&cfg, &at(last), &at(now), ignore_time);
+        assert!(due("2026-01-05T09:58:00Z", "2026-01-05T10:03:00Z", false));
+        assert!(!due("2026-01-05T10:03:00Z", "2026-01-05T10:08:00Z", false));
+        assert!(due("2026-01-05T10:03:00Z", "2026-01-05T10:08:00Z", true));
+    }
+
+    #[test]
+    fn reviewer_report_hides_failing_ci() {
+        let mut cfg = guild_config();
+        cfg.users
+            .get_mut(&model::DiscordUserId("1".to_string()))
+            .unwrap()
+            .hide_failing_ci = true;
+        let messages = render(&cfg, "1");
+        assert!(!messages.iter().any(|m| m.contains("[PR #102]")));
+        assert!(messages.iter().any(|m| m.contains("[PR #101]")));
+    }
+
+    #[test]
+    fn multiple_repos() {
+        let mut cfg = guild_config();
+        cfg.repos.push(repo("carbon-tools"));
+        let messages = render(&cfg, "1");
+
+        let new_requests = &messages[0];
+        assert!(new_requests.contains("\n* `carbon-lang` [PR #101]"));
+        assert!(new_requests.contains(
+            "\n* `carbon-tools` [PR #7](<https://github.com/carbon-language/carbon-tools/pull/7>)"
+        ));
+        assert!(messages[2].contains("\n* `carbon-lang` [Issue #201]"));
+
+        // Repositories with the same name are told apart by their owners.
+        cfg.repos.push(model::RepoConfig {
+            owner: "carbon-fork".to_string(),
+            name: "carbon-lang".to_string(),
+        });
+        let messages = render(&cfg, "1");
+        assert!(messages[0].contains("\n* `carbon-language/carbon-lang` [PR #101]"));
+        assert!(messages[0].contains("\n* `carbon-language/carbon-tools` [PR #7]"));
+    }
+
+    #[test]
+    fn custom_issue_labels() {
+        let mut cfg = guild_config();
+        cfg.issue_labels
+            .urgency
+            .insert("blocking work".to_string(), model::Urgency::Weekly);
+        cfg.issue_labels.unlabeled = model::Urgency::Immediate;
+        let messages = render(&cfg, "1");
+
+        let blocking = messages
+            .iter()
+            .find(|m| m.starts_with(":fire_engine:"))
+            .unwrap();
+        assert!(blocking.contains("[Issue #202]"));
+        assert!(!blocking.contains("[Issue #201]"));
+        let weekly = messages
+            .iter()
+            .find(|m| m.starts_with(":chipmunk:"))
+            .unwrap();
+        assert!(weekly.contains("[Issue #201]"));
+    }
+
+    #[test]
+    fn issues_go_to_involved_leads() {
+        let mut cfg = guild_config();
+        let mut erin = user("erin");
+        erin.lead = true;
+        cfg.users
+            .insert(model::DiscordUserId("3".to_string()), erin);
+
+        let bob = render(&cfg, "1");
+        let blocking = bob.iter().find(|m| m.starts_with(":fire_engine:")).unwrap();
+        // Nobody is involved in #201, so all leads hear about it.
+        assert!(blocking.contains("[Issue #201]"));
+        assert!(!blocking.contains("[Issue #205]"));
+        assert!(blocking.contains("[Issue #206]"));
+        let weekly = bob.iter().find(|m| m.starts_with(":chipmunk:")).unwrap();
+        assert!(weekly.contains("[Issue #205]"));
+
+        let erin = render(&cfg, "3");
+        let blocking = erin
+            .iter()
+            .find(|m| m.starts_with(":fire_engine:"))
+            .unwrap();
+        assert!(blocking.contains("[Issue #201]"));
+        assert!(blocking.contains("[Issue #205]"));
+        assert!(!blocking.contains("[Issue #206]"));
+        let weekly = erin.iter().find(|m| m.starts_with(":chipmunk:")).unwrap();
+        assert!(weekly.contains("[Issue #206]"));
+        assert!(!weekly.contains("[Issue #205]"));
+    }
+
+    #[test]
+    fn author_report() {
+        let messages = render(&guild_config(), "2");
+        assert_eq!(messages.len(), 1, "{:#?}", messages);
+
+        let authored = &messages[0];
+        assert!(authored.starts_with(":pencil: Your open PRs <@2>"));
+        assert!(authored
+            .contains("[PR #101](<https://github.com/carbon-language/carbon-lang/pull/101>)"));
+        assert!(authored.contains(":construction: changes requested"));
+        let status_of = |number: &str| {
+            authored
+                .lines()
+                .find(|line| line.contains(&format!("[PR #{}]", number)))
+                .unwrap()
+        };
+        // Changes requested before the author pushed and asked again are
+        // answered.
+        assert!(status_of("110").contains(":hourglass: waiting for review"));
+        // One approval isn't enough while another review is requested.
+        assert!(status_of("111").contains(":hourglass: waiting for review"));
+        // Drafts are left out.
+        assert!(!authored.contains("[PR #105]"));
+    }
+
+    #[test]
+    fn triager_report() {
+        let mut cfg = guild_config();
+        // Nobody hears about unassigned PRs until someone triages them.
+        assert!(!render(&cfg, "1")
+            .iter()
+            .any(|m| m.starts_with(":inbox_tray:")));
+
+        cfg.users
+            .get_mut(&model::DiscordUserId("1".to_string()))
+            .unwrap()
+            .triager = true;
+        let messages = render(&cfg, "1");
+        let unassigned = messages
+            .iter()
+            .find(|m| m.starts_with(":inbox_tray: PRs without a reviewer <@1>"))
+            .unwrap();
+        // The oldest come first.
+        let pr_107 = unassigned.find("[PR #107]").unwrap();
+        let pr_106 = unassigned.find("[PR #106]").unwrap();
+        assert!(pr_107 < pr_106, "{}", unassigned);
+        // Drafts and PRs that have been reviewed are left out.
+        assert!(!unassigned.contains("[PR #105]"));
+        assert!(!unassigned.contains("[PR #108]"));
+        assert!(!unassigned.contains("[PR #101]"));
+    }
+
+    #[test]
+    fn code_owners_report() {
+        let mut cfg = guild_config();
+        cfg.users
+            .insert(model::DiscordUserId("3".to_string()), user("erin"));
+        cfg.users
+            .insert(model::DiscordUserId("4".to_string()), user("carol"));
+        cfg.github_teams.insert(
+            "docs".to_string(),
+            vec![
+                model::GithubUserName::from_str("carol"),
+                model::GithubUserName::from_str("erin"),
+            ],
+        );
+
+        let messages = render(&cfg, "3");
+        assert_eq!(messages.len(), 1, "{:#?}", messages);
+        let owned = &messages[0];
+        assert!(owned.starts_with(":compass: Unassigned PRs in your area <@3>"));
+        // The oldest come first.
+        let pr_107 = owned.find("[PR #107]").unwrap();
+        let pr_106 = owned.find("[PR #106]").unwrap();
+        assert!(pr_107 < pr_106, "{}", owned);
+        // PRs that have been reviewed are left out.
+        assert!(!owned.contains("[PR #108]"));
+
+        // Authors aren't asked to review their own PRs.
+        assert!(!render(&cfg, "4").iter().any(|m| m.starts_with(":compass:")));
+    }
+
+    #[test]
+    fn watched_paths_report() {
+        let mut cfg = guild_config();
+        let mut erin = user("erin");
+        erin.watched_paths = vec!["toolchain/**/*.cpp".to_string(), "*.md".to_string()];
+        cfg.users
+            .insert(model::DiscordUserId("3".to_string()), erin);
+        cfg.users
+            .get_mut(&model::DiscordUserId("1".to_string()))
+            .unwrap()
+            .watched_paths = vec!["toolchain/".to_string()];
+
+        let messages = render(&cfg, "3");
+        let watched = messages
+            .iter()
+            .find(|m| m.starts_with(":telescope: PRs in your areas <@3>"))
+            .unwrap();
+        assert!(watched.contains("[PR #106]"));
+        assert!(watched.contains("[PR #107]"));
+        assert!(watched.contains("[PR #108]"));
+        assert!(!watched.contains("[PR #101]"));
+
+        // PRs that the user already reviews are left out.
+        let messages = render(&cfg, "1");
+        let watched = messages
+            .iter()
+            .find(|m| m.starts_with(":telescope:"))
+            .unwrap();
+        assert!(watched.contains("[PR #108]"));
+        assert!(!watched.contains("[PR #106]"));
+        assert!(!watched.contains("[PR #101]"));
+    }
+
+    #[test]
+    fn former_reviewers_report() {
+        let mut cfg = guild_config();
+        let dave_id = model::DiscordUserId("4".to_string());
+        let mut dave = user("dave");
+        dave.reviewed_prs.insert(
+            "carbon-language/carbon-lang#108".to_string(),
+            "a108-old".to_string(),
+        );
+        dave.reviewed_prs.insert(
+            "carbon-language/carbon-lang#99".to_string(),
+            "a99".to_string(),
+        );
+        cfg.users.insert(dave_id.clone(), dave);
+
+        // Dave is no longer requested on #108, but hears about the commits
+        // pushed since his review.
+        let messages = render(&cfg, "4");
+        let updated = messages
+            .iter()
+            .find(|m| m.starts_with(":arrows_counterclockwise: PRs updated since your review <@4>"))
+            .unwrap();
+        assert!(updated.contains("[PR #108]"));
+
+        // His review is still of the old commit, and closed PRs are forgotten.
+        let (prs, _) = load_fixture(&cfg);
+        record_reviewed_prs(&mut cfg, &prs);
+        let reviewed_prs = &cfg.users[&dave_id].reviewed_prs;
+        assert_eq!(reviewed_prs.len(), 1);
+        assert_eq!(reviewed_prs["carbon-language/carbon-lang#108"], "a108-old");
+
+        // Once he reviews the head of the PR, it's quiet again.
+        cfg.users.get_mut(&dave_id).unwrap().reviewed_prs.insert(
+            "carbon-language/carbon-lang#108".to_string(),
+            "a108".to_string(),
+        );
+        let messages = render(&cfg, "4");
+        assert!(messages.iter().all(|m| !m.contains("[PR #108]")));
+    }
+
+    #[test]
+    fn sections_are_edited_in_place() {
+        let message = |id: &str, content: &str| model::PostedMessage {
+            message_id: model::DiscordMessageId(id.to_string()),
+            content: content.to_string(),
+            embed: None,
+        };
+        let posted = model::PostedSection {
+            channel_id: Default::default(),
+            discord_user_id: None,
+            messages: vec![
+                message("1", "header\n* #101 waiting 1d"),
+                message("2", "header\n* #102"),
+            ],
+            keys: vec!["#101".to_string(), "#102".to_string()],
+        };
+        let section = |messages: &[&str], keys: &[&str]| ReportSection {
+            header: "header".to_string(),
+            messages: messages.iter().map(|m| m.to_string()).collect(),
+            embeds: Vec::new(),
+            keys: keys.iter().map(|k| k.to_string()).collect(),
+        };
+        let id = |id: &str| model::DiscordMessageId(id.to_string());
+        let text = |content: &str| ReportMessage {
+            content: content.to_string(),
+            embed: None,
+        };
+        let plan = |section: &ReportSection, repost| {
+            plan_section_update(section, model::ReportFormat::Text, &posted, repost)
+        };
+
+        // Changed messages are edited and left over ones are deleted.
+        let updated = section(&["header\n* #101 waiting 2d"], &["#101"]);
+        assert_eq!(
+            plan(&updated, false),
+            [
+                MessageUpdate::Edit(id("1"), text("header\n* #101 waiting 2d")),
+                MessageUpdate::Delete(id("2")),
+            ]
+        );
+
+        // Unchanged messages are kept.
+        let unchanged = section(
+            &["header\n* #101 waiting 1d", "header\n* #102"],
+            &["#101", "#102"],
+        );
+        assert_eq!(
+            plan(&unchanged, false),
+            [
+                MessageUpdate::Keep(posted.messages[0].clone()),
+                MessageUpdate::Keep(posted.messages[1].clone()),
+            ]
+        );
+
+        // Something new, or asking to be pinged, posts the section afresh.
+        let added = section(&["header\n* #101\n* #103"], &["#101", "#103"]);
+        let reposted = [
+            MessageUpdate::Delete(id("1")),
+            MessageUpdate::Delete(id("2")),
+            MessageUpdate::Post(text("header\n* #101\n* #103")),
+        ];
+        assert_eq!(plan(&added, false), reposted);
+        assert_eq!(plan(&unchanged, true)[..2], reposted[..2]);
+    }
+
+    #[test]
+    fn embeds() {
+        let (prs, issues) = load_fixture(&guild_config());
+        let discord_user_id = model::DiscordUserId("1".to_string());
+        let sections = render_report_for_user(
+            &prs,
+            &issues,
+            RepoTags::Off,
+            &discord_user_id,
+            &guild_config().users[&discord_user_id],
+        );
+
+        let messages = sections[0].messages_in(model::ReportFormat::Embeds);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].content, ":notepad_spiral: PRs for review <@1>");
+        let embed = messages[0].embed.as_ref().unwrap();
+        assert_eq!(embed.color, embed_color(None));
+        let field = embed
+            .fields
+            .iter()
+            .find(|field| field.name.starts_with("PR #101: "))
+            .unwrap();
+        assert!(field.value.contains("by **alice**"));
+        assert!(field.value.contains("CI passing"));
+        // The section's own details are kept in the embeds.
+        let field = embed
+            .fields
+            .iter()
+            .find(|field| field.name.starts_with("PR #104: "))
+            .unwrap();
+        assert!(field.value.contains(":alarm_clock: waiting "));
+        assert!(field.value.contains("(via team toolchain)"));
+
+        // Leads issues are colored by their urgency.
+        let issues_section = sections
+            .iter()
+            .find(|section| section.header.starts_with(BLOCKING_ISSUES_HEADER))
+            .unwrap();
+        assert_eq!(
+            issues_section.embeds[0].color,
+            embed_color(Some(model::Urgency::EveryReport))
+        );
+
+        // Long sections are split to stay within Discord's limits.
+        let long_names: Vec<String> = (0..60).map(|i| format!("{:0300}", i)).collect();
+        let section =
+            render_alert_messages("header".to_string(), long_names.iter().collect(), |name| {
+                name.clone()
+            });
+        assert!(section.embeds.len() > 1);
+        for embed in &section.embeds {
+            assert!(embed.fields.len() <= EMBED_FIELDS_LIMIT);
+            let total: usize = embed
+                .fields
+                .iter()
+                .map(|field| field.name.chars().count() + field.value.chars().count())
+                .sum();
+            assert!(total <= EMBED_TOTAL_LIMIT);
+            assert!(embed
+                .fields
+                .iter()
+                .all(|field| field.name.chars().count() <= EMBED_FIELD_NAME_LIMIT));
+        }
+        let fields: usize = section.embeds.iter().map(|embed| embed.fields.len()).sum();
+        assert_eq!(fields, long_names.len());
+    }
+
+    #[test]
+    fn saved_searches() {
+        let cfg = guild_config();
+        let results = futures::executor::block_on(github::search(
+            fake_github(),
+            r#"is:open label:"good first issue" no:assignee"#,
+            &cfg.repos,
+        ))
+        .unwrap();
+        let search = SearchReport {
+            discord_user_id: Some(model::DiscordUserId("1".to_string())),
+            name: "Up for grabs".to_string(),
+            results,
+        };
+        let sections = render_search_report(&search, RepoTags::Off);
+        assert_eq!(
+            sections[0].messages,
+            [":mag: **Up for grabs** <@1>\n* [Issue #301]\
+            (<https://github.com/carbon-language/carbon-lang/issues/301>) Add a `--version flag`"]
+        );
+
+        let mut saved_search = model::SavedSearch {
+            name: search.name,
+            query: String::new(),
+            cadence: model::Cadence::Weekly,
+            last_reported_at: None,
+        };
+        let now = Utc::now();
+        assert!(saved_search.is_due(now));
+        saved_search.last_reported_at = Some(now - Duration::days(6));
+        assert!(!saved_search.is_due(now));
+        saved_search.last_reported_at = Some(now - Duration::days(7) + Duration::minutes(5));
+        assert!(saved_search.is_due(now));
+    }
+
+    #[test]
+    fn reports_are_told_apart() {
+        let mut cfg = guild_config();
+        cfg.escalation.after_workdays = Some(5);
+        assert!(render(&cfg, "1").iter().all(|m| is_report(m)));
+        let bob = model::DiscordUserId("1".to_string());
+        assert!(is_report(&search_header("Up for grabs", Some(&bob))));
+        assert!(is_report(&search_header("Up for grabs", None)));
+
+        // Escalations and replies to commands are left alone.
+        let (prs, _) = load_fixture(&cfg);
+        let (section, _) = render_escalations(&prs, &cfg, RepoTags::Off, Utc::now());
+        assert!(!is_report(&section.messages[0]));
+        assert!(!is_report(":white_check_mark: You have been removed."));
+    }
+
+    #[test]
+    fn delivery() {
+        use model::Delivery;
+        use Placement::*;
+        let plan = |dm, channel| DeliveryPlan { dm, channel };
+
+        assert_eq!(
+            plan_delivery(Delivery::Channel, false, false),
+            plan(Skip, Send)
+        );
+        assert_eq!(plan_delivery(Delivery::Dm, false, false), plan(Send, Clear));
+        assert_eq!(
+            plan_delivery(Delivery::Both, false, false),
+            plan(Send, Send)
+        );
+        // Reports left in direct messages are deleted once they go to the
+        // channel only.
+        assert_eq!(
+            plan_delivery(Delivery::Channel, true, false),
+            plan(Clear, Send)
+        );
+        // Reports that can't reach the user's direct messages go to the
+        // channel instead.
+        assert_eq!(plan_delivery(Delivery::Dm, true, true), plan(Skip, Send));
+        assert_eq!(plan_delivery(Delivery::Both, true, true), plan(Skip, Send));
+        assert_eq!(
+            plan_delivery(Delivery::Channel, true, true),
+            plan(Skip, Send)
+        );
+    }
+
+    #[test]
+    fn threads() {
+        let channel_id = |id: &str| {
+            model::DiscordChannelId(model::DiscordGuildId("1".to_string()), id.to_string())
+        };
+        let channel = channel_id("10");
+        let thread = model::ReportThread {
+            thread_id: channel_id("11"),
+            parent_id: channel.clone(),
+            kind: model::ReportThreads::Private,
+        };
+        let thread_id = thread.thread_id.clone();
+        assert_eq!(
+            plan_thread(model::ReportThreads::Off, &channel, None),
+            ThreadPlan::Channel
+        );
+        assert_eq!(
+            plan_thread(model::ReportThreads::Private, &channel, None),
+            ThreadPlan::Create(None)
+        );
+        // The thread is used without looking it up.
+        assert_eq!(
+            plan_thread(model::ReportThreads::Private, &channel, Some(&thread)),
+            ThreadPlan::Use(thread_id.clone())
+        );
+        // Turning threads off takes the user out of theirs.
+        assert_eq!(
+            plan_thread(model::ReportThreads::Off, &channel, Some(&thread)),
+            ThreadPlan::Leave(thread_id.clone())
+        );
+        // A new kind of thread, or a new report channel, replaces the thread.
+        assert_eq!(
+            plan_thread(model::ReportThreads::Public, &channel, Some(&thread)),
+            ThreadPlan::Create(Some(thread_id.clone()))
+        );
+        let new_channel = channel_id("12");
+        assert_eq!(
+            plan_thread(model::ReportThreads::Private, &new_channel, Some(&thread)),
+            ThreadPlan::Create(Some(thread_id))
+        );
+    }
+
+    impl ReportItem for String {
+        fn key(&self) -> &str {
+            self
+        }
+
+        fn embed_field(&self) -> EmbedField {
+            embed_field(self.clone(), vec![self.clone()])
+        }
+    }
+
+    #[test]
+    fn long_reports_are_split() {
+        let alerts: Vec<String> = (0..100).map(|i| format!("{:040}", i)).collect();
+        let section =
+            render_alert_messages("header".to_string(), alerts.iter().collect(), |a| a.clone());
+        assert!(section.messages.len() > 1);
+        assert!(section
+            .messages
+            .iter()
+            .all(|m| m.starts_with("header") && m.len() <= 2000));
+        let lines: usize = section.messages.iter().map(|m| m.lines().count() - 1).sum();
+        assert_eq!(lines, alerts.len());
+    }
+}
@@ -9,6 +9,46 @@ use poise::serenity_prelude as serenity;
 use crate::discord::DiscordError;
 use crate::model;
 
+/// Whether the Discord error is for something that no longer exists, such as
+/// a message or thread that someone deleted by hand.
+pub fn is_not_found(e: &serenity::Error) -> bool {
+    match e {
+        serenity::Error::Http(e) => e.status_code().map(|code| code.as_u16()) == Some(404),
+        _ => false,
+    }
+}
+
+/// Deletes a message by its ID. Messages that are already gone are skipped.
+pub async fn delete_message(
+    http: &serenity::Http,
+    discord_channel_id: &model::DiscordChannelId,
+    discord_message_id: &model::DiscordMessageId,
+) -> Result<(), DiscordError> {
+    match http
+        .delete_message(discord_channel_id.into(), discord_message_id.into(), None)
+        .await
+    {
+        Err(e) if !is_not_found(&e) => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// Deletes the messages that fizz posted for report sections that are gone.
+pub async fn delete_posted_sections(
+    http: &serenity::Http,
+    sections: impl IntoIterator<Item = model::PostedSection>,
+) -> Result<(), DiscordError> {
+    for section in sections {
+        for message in section.messages {
+            delete_message(http, &section.channel_id, &message.message_id).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Deletes the messages that `f` picks out of the channel's whole history.
+/// This pages through every message in the channel, so it is slow and costly
+/// on a busy channel.
 pub async fn delete_messages<F: FnMut(&serenity::Message) -> bool>(
     http: Arc<serenity::Http>,
     discord_channel_id: model::DiscordChannelId,
@@ -16,24 +56,27 @@ pub async fn delete_messages<F: FnMut(&serenity::Message) -> bool>(
 ) -> Result<(), DiscordError> {
     let channel_id: serenity::ChannelId = discord_channel_id.into();
 
+    // Pages come newest first, so each page continues from the oldest message
+    // of the one before.
+    let mut before = None;
     loop {
-        // TODO: Do stuff with MessagePagination to avoid asking for the same
-        // messages repeatedly.
-        let messages = http.get_messages(channel_id, None, None).await?;
-        if messages.is_empty() {
+        let messages = http
+            .get_messages(
+                channel_id,
+                before.map(serenity::MessagePagination::Before),
+                Some(100),
+            )
+            .await?;
+        let Some(oldest) = messages.last() else {
             break;
-        }
+        };
+        before = Some(oldest.id);
 
-        let mut deleted = false;
         for m in messages {
             if f(&m) {
                 http.delete_message(channel_id, m.id, None).await?;
-                deleted = true;
             }
         }
-        if !deleted {
-            break;
-        }
     }
 
     Ok(())
@@ -5,7 +5,10 @@
 use poise::serenity_prelude as serenity;
 use std::path::PathBuf;
 
+use crate::github;
+
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum Error {
     #[allow(dead_code)]
     Silent,
@@ -13,10 +16,62 @@ pub enum Error {
     IoError(Option<PathBuf>, std::io::Error),
     ConfigFileMissing(PathBuf),
     ConfigParsingError(PathBuf, String),
+    FixtureParsingError(PathBuf, String),
+    GithubCredentialsMissing(String),
+    GithubCredentialsInvalid(String, String),
+    GithubClientFailed(octocrab::Error),
     FailedToGetIssues(octocrab::Error),
     FailedToGetPRs(octocrab::Error),
+    FailedToGetReviews(octocrab::Error),
+    FailedToGetChecks(octocrab::Error),
+    FailedToGetTeamMembers(octocrab::Error),
+    FailedToGetTimeline(octocrab::Error),
+    FailedToGetCodeOwners(octocrab::Error),
+    FailedToGetPrFiles(octocrab::Error),
+    FailedToSearch(octocrab::Error),
+    FailedToGetRateLimit(octocrab::Error),
+    WebhookPayloadInvalid(String, String),
+    WebhookSecretMissing(String),
     DiscordTokenMissing(String),
-    DiscordConnectFailed(serenity::Error),
+    DiscordConnectFailed(Box<serenity::Error>),
+}
+
+impl Error {
+    /// Whether Github refused a request because of its rate limit, rather than
+    /// for any other reason.
+    pub fn is_github_rate_limited(&self) -> bool {
+        use Error::*;
+        match self {
+            FailedToGetIssues(e)
+            | FailedToGetPRs(e)
+            | FailedToGetReviews(e)
+            | FailedToGetChecks(e)
+            | FailedToGetTeamMembers(e)
+            | FailedToGetTimeline(e)
+            | FailedToGetCodeOwners(e)
+            | FailedToGetPrFiles(e)
+            | FailedToSearch(e) => github::is_rate_limit_error(e),
+            _ => false,
+        }
+    }
+}
+
+/// Describes a failed Github request, calling out when the rate limit was the
+/// cause.
+fn write_github_failure(
+    f: &mut std::fmt::Formatter<'_>,
+    what: &str,
+    e: &octocrab::Error,
+) -> std::fmt::Result {
+    if github::is_rate_limit_error(e) {
+        write!(
+            f,
+            "unable to get GitHub {}, rate limit exceeded: {}",
+            what, e
+        )
+    } else {
+        write!(f, "unable to get GitHub {}: {}", what, e)
+    }
 }
 
 impl std::error::Error for Error {}
@@ -36,8 +91,43 @@ impl std::fmt::Display for Error {
                 msg,
                 path.display()
             ),
-            FailedToGetIssues(e) => write!(f, "unable to get GitHub Issues: {}", e),
-            FailedToGetPRs(e) => write!(f, "unable to get GitHub PRs: {}", e),
+            GithubCredentialsMissing(var) => {
+                write!(
+                    f,
+                    "missing github credentials in {} environment variable",
+                    var
+                )
+            }
+            GithubCredentialsInvalid(var, value) => write!(
+                f,
+                "invalid github credentials in {} environment variable: {}",
+                var, value
+            ),
+            GithubClientFailed(e) => write!(f, "unable to create GitHub client: {}", e),
+            FixtureParsingError(path, msg) => write!(
+                f,
+                "error parsing Github fixture: {} (path: {})",
+                msg,
+                path.display()
+            ),
+            FailedToGetIssues(e) => write_github_failure(f, "Issues", e),
+            FailedToGetPRs(e) => write_github_failure(f, "PRs", e),
+            FailedToGetReviews(e) => write_github_failure(f, "PR reviews", e),
+            FailedToGetChecks(e) => write_github_failure(f, "CI checks", e),
+            FailedToGetTeamMembers(e) => write_github_failure(f, "team members", e),
+            FailedToGetTimeline(e) => write_github_failure(f, "PR timeline", e),
+            FailedToGetCodeOwners(e) => write_github_failure(f, "CODEOWNERS", e),
+            FailedToGetPrFiles(e) => write_github_failure(f, "PR files", e),
+            FailedToSearch(e) => write_github_failure(f, "search results", e),
+            FailedToGetRateLimit(e) => write!(f, "unable to get GitHub rate limit: {}", e),
+            WebhookPayloadInvalid(event, msg) => {
+                write!(f, "invalid GitHub `{}` webhook payload: {}", event, msg)
+            }
+            WebhookSecretMissing(var) => write!(
+                f,
+                "missing GitHub webhook secret in {} environment variable",
+                var
+            ),
             DiscordTokenMissing(var) => {
                 write!(f, "missing discord token in {} environment variable", var)
             }
@@ -0,0 +1,278 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::stream::{self, BoxStream, StreamExt};
+use octocrab::etag::EntityTag;
+use octocrab::{FromResponse, Octocrab, Page};
+
+use super::rate_limit::{self, RateLimitTracker};
+use crate::error::Error;
+
+/// How long a cached page is used without asking Github whether it changed.
+/// This is shorter than the time between polls, so each poll checks in with
+/// Github once, and guilds watching the same repository share the result.
+const FRESH_FOR: Duration = Duration::from_secs(60);
+
+struct CachedPage<T> {
+    /// The URL of the first page of the list that this page is part of. Later
+    /// pages are linked by repository id, so this is how to find the pages of
+    /// a repository.
+    first_url: String,
+    etag: Option<EntityTag>,
+    items: Vec<T>,
+    next: Option<String>,
+    /// When Github last confirmed the page, or `None` if it may have changed
+    /// since.
+    checked_at: Option<Instant>,
+    /// When the page was last asked for, to drop the pages of lists that are
+    /// no longer looked at.
+    used_at: Instant,
+}
+
+/// Pages of Github list responses, keyed by their URL.
+///
+/// Pages are revalidated with `If-None-Match` once they are no longer fresh.
+/// Github does not count a `304 Not Modified` response against the rate
+/// limit, so polling a repository that has not changed is free.
+pub struct PageCache<T> {
+    pages: Mutex<HashMap<String, CachedPage<T>>>,
+    rate_limit: Arc<RateLimitTracker>,
+}
+
+impl<T> PageCache<T>
+where
+    T: serde::de::DeserializeOwned + Clone + Send + 'static,
+{
+    pub fn new(rate_limit: Arc<RateLimitTracker>) -> Arc<Self> {
+        Arc::new(Self {
+            pages: Mutex::new(HashMap::new()),
+            rate_limit,
+        })
+    }
+
+    /// Stops using the cached pages of lists whose URL starts with `prefix`
+    /// without revalidating them first.
+    pub fn expire(&self, prefix: &str) {
+        for page in self.pages.lock().unwrap().values_mut() {
+            if page.first_url.starts_with(prefix) {
+                page.checked_at = None;
+            }
+        }
+    }
+
+    /// Applies a change to an item in the lists whose URL starts with `prefix`.
+    /// The item that `is_same` matches is taken out, and `updated` gives what
+    /// to put at the end of each list in its place, given the URL of the
+    /// list's first page. The end is where a list sorted by when items were
+    /// last updated has it.
+    ///
+    /// Pages keep their ETags, so once they are no longer fresh, Github sends
+    /// the lists again.
+    pub fn update(
+        &self,
+        prefix: &str,
+        is_same: impl Fn(&T) -> bool,
+        updated: impl Fn(&str) -> Option<T>,
+    ) {
+        let mut pages = self.pages.lock().unwrap();
+        let lists: HashSet<String> = pages
+            .values()
+            .filter(|page| page.first_url.starts_with(prefix))
+            .map(|page| page.first_url.clone())
+            .collect();
+        for first_url in lists {
+            let mut list: Vec<&mut CachedPage<T>> = pages
+                .values_mut()
+                .filter(|page| page.first_url == first_url)
+                .collect();
+            for page in list.iter_mut() {
+                page.items.retain(|item| !is_same(item));
+            }
+            let Some(item) = updated(&first_url) else {
+                continue;
+            };
+            match list.iter_mut().find(|page| page.next.is_none()) {
+                Some(last_page) => last_page.items.push(item),
+                // Without the last page, there's nowhere to put the item, so
+                // fetch the list again.
+                None => {
+                    for page in list.iter_mut() {
+                        page.checked_at = None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Drops the pages that haven't been asked for in `unused_for`, such as
+    /// those of searches that were removed.
+    pub fn prune(&self, unused_for: Duration) {
+        self.pages
+            .lock()
+            .unwrap()
+            .retain(|_, page| page.used_at.elapsed() < unused_for);
+    }
+
+    /// Drops the least recently used pages until there are at most
+    /// `max_pages`.
+    pub fn truncate(&self, max_pages: usize) {
+        let mut pages = self.pages.lock().unwrap();
+        if pages.len() <= max_pages {
+            return;
+        }
+        let mut used: Vec<(Instant, String)> = pages
+            .iter()
+            .map(|(url, page)| (page.used_at, url.clone()))
+            .collect();
+        used.sort();
+        for (_, url) in &used[..used.len() - max_pages] {
+            pages.remove(url);
+        }
+    }
+
+    /// Calls `f` with each cached item, along with the URL of the first page
+    /// of the list that it is in.
+    pub fn for_each_item(&self, mut f: impl FnMut(&str, &T)) {
+        for page in self.pages.lock().unwrap().values() {
+            for item in &page.items {
+                f(&page.first_url, item);
+            }
+        }
+    }
+
+    /// Gets the items on the page at `url`, and the URL of the next page.
+    async fn get_page(
+        &self,
+        octo: &Octocrab,
+        first_url: &str,
+        url: &str,
+    ) -> Result<(Vec<T>, Option<String>), octocrab::Error> {
+        let mut headers = http::HeaderMap::new();
+        {
+            let mut pages = self.pages.lock().unwrap();
+            if let Some(cached) = pages.get_mut(url) {
+                cached.used_at = Instant::now();
+                if cached.checked_at.is_some_and(|at| at.elapsed() < FRESH_FOR) {
+                    return Ok((cached.items.clone(), cached.next.clone()));
+                }
+                if let Some(etag) = &cached.etag {
+                    EntityTag::insert_if_none_match_header(&mut headers, etag.clone())?;
+                }
+            }
+        }
+
+        let response = octo._get_with_headers(url, Some(headers)).await?;
+        self.rate_limit.record_headers(response.headers());
+        if response.status() == http::StatusCode::NOT_MODIFIED {
+            let mut pages = self.pages.lock().unwrap();
+            // The page is only missing if it was never cached, in which case
+            // Github would have no etag to compare with.
+            if let Some(cached) = pages.get_mut(url) {
+                cached.checked_at = Some(Instant::now());
+                return Ok((cached.items.clone(), cached.next.clone()));
+            }
+        }
+
+        let etag = EntityTag::extract_from_response(&response);
+        let response = match octocrab::map_github_error(response).await {
+            Ok(response) => response,
+            Err(e) => {
+                if rate_limit::is_rate_limit_error(&e) {
+                    self.rate_limit.record_limited();
+                }
+                return Err(e);
+            }
+        };
+        let page = Page::<T>::from_response(response).await?;
+        let next = page.next.map(|uri| uri.to_string());
+        self.pages.lock().unwrap().insert(
+            url.to_string(),
+            CachedPage {
+                first_url: first_url.to_string(),
+                etag,
+                items: page.items.clone(),
+                next: next.clone(),
+                checked_at: Some(Instant::now()),
+                used_at: Instant::now(),
+            },
+        );
+        Ok((page.items, next))
+    }
+
+    /// Streams the items of every page of a Github list, starting at `url`.
+    /// Like `stream_pages`, the next page is only requested from Github once
+    /// the items of the previous page have been consumed.
+    pub fn stream(
+        self: Arc<Self>,
+        octo: Arc<Octocrab>,
+        url: String,
+        to_error: fn(octocrab::Error) -> Error,
+    ) -> BoxStream<'static, Result<T, Error>> {
+        let first_url = url.clone();
+        stream::unfold(Some(url), move |url| {
+            let cache = self.clone();
+            let octo = octo.clone();
+            let first_url = first_url.clone();
+            async move {
+                let url = url?;
+                match cache.get_page(&octo, &first_url, &url).await {
+                    Ok((items, next)) => Some((items.into_iter().map(Ok).collect(), next)),
+                    Err(e) => Some((vec![Err(to_error(e))], None)),
+                }
+            }
+        })
+        .flat_map(stream::iter)
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(first_url: &str, items: Vec<u64>, next: Option<&str>) -> CachedPage<u64> {
+        CachedPage {
+            first_url: first_url.to_string(),
+            etag: None,
+            items,
+            next: next.map(|next| next.to_string()),
+            checked_at: Some(Instant::now()),
+            used_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn update() {
+        let cache = PageCache::<u64>::new(Arc::new(RateLimitTracker::default()));
+        let first = "/repos/o/n/pulls?page=1";
+        let other = "/repos/o/other/pulls?page=1";
+        {
+            let mut pages = cache.pages.lock().unwrap();
+            pages.insert(first.to_string(), page(first, vec![1, 2], Some("2")));
+            pages.insert("2".to_string(), page(first, vec![3], None));
+            pages.insert(other.to_string(), page(other, vec![2], None));
+        }
+        let items = |url: &str| cache.pages.lock().unwrap()[url].items.clone();
+
+        // Updated items move to the end of the list.
+        cache.update("/repos/o/n/", |item| *item == 2, |_| Some(2));
+        assert_eq!(items(first), vec![1]);
+        assert_eq!(items("2"), vec![3, 2]);
+        assert_eq!(items(other), vec![2]);
+
+        // Items that leave the list are taken out.
+        cache.update("/repos/o/n/", |item| *item == 3, |_| None);
+        assert_eq!(items("2"), vec![2]);
+
+        // Without the last page, the list is fetched again.
+        cache.pages.lock().unwrap().remove("2");
+        cache.update("/repos/o/n/", |item| *item == 4, |_| Some(4));
+        assert_eq!(items(first), vec![1]);
+        assert!(cache.pages.lock().unwrap()[first].checked_at.is_none());
+    }
+}
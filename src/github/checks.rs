@@ -0,0 +1,88 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::sync::Arc;
+
+use octocrab::models::{CombinedStatus, StatusState};
+use octocrab::params::repos::Commitish;
+use octocrab::Octocrab;
+use serde::Deserialize;
+
+use crate::error::Error;
+
+/// The overall result of the CI checks and statuses on a commit.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CiStatus {
+    /// There are no checks on the commit, or they were not looked up.
+    #[default]
+    Unknown,
+    Passing,
+    Failing,
+    Pending,
+}
+
+/// Check run conclusions that mean the check did not pass.
+const FAILED_CONCLUSIONS: &[&str] = &["failure", "timed_out", "startup_failure", "action_required"];
+
+/// Finds the combined result of the check runs and commit statuses on the
+/// commit `sha`.
+pub(super) async fn get_ci_status(
+    octo: Arc<Octocrab>,
+    repo_owner: &str,
+    repo_name: &str,
+    sha: &str,
+) -> Result<CiStatus, Error> {
+    // Check runs are listed in an object rather than a plain page, so
+    // `stream_pages` can't follow them. Their total count says when to stop.
+    let mut check_runs = Vec::new();
+    for page in 1u32.. {
+        let list = match octo
+            .checks(repo_owner, repo_name)
+            .list_check_runs_for_git_ref(Commitish(sha.to_string()))
+            .per_page(100)
+            .page(page)
+            .send()
+            .await
+        {
+            Ok(list) => list,
+            Err(e) => return Err(Error::FailedToGetChecks(e)),
+        };
+        let last_page = list.check_runs.is_empty();
+        check_runs.extend(list.check_runs);
+        if last_page || check_runs.len() as u64 >= list.total_count {
+            break;
+        }
+    }
+
+    // The octocrab helper for statuses only accepts branches and tags.
+    let route = format!("/repos/{}/{}/commits/{}/status", repo_owner, repo_name, sha);
+    let statuses = match octo.get::<CombinedStatus, _, ()>(route, None).await {
+        Ok(combined) => combined.statuses,
+        Err(e) => return Err(Error::FailedToGetChecks(e)),
+    };
+
+    if check_runs.is_empty() && statuses.is_empty() {
+        return Ok(CiStatus::Unknown);
+    }
+
+    let failed = check_runs.iter().any(|run| {
+        run.conclusion
+            .as_deref()
+            .is_some_and(|c| FAILED_CONCLUSIONS.contains(&c))
+    }) || statuses
+        .iter()
+        .any(|s| matches!(s.state, StatusState::Failure | StatusState::Error));
+    if failed {
+        return Ok(CiStatus::Failing);
+    }
+
+    let pending = check_runs.iter().any(|run| run.conclusion.is_none())
+        || statuses.iter().any(|s| s.state == StatusState::Pending);
+    if pending {
+        return Ok(CiStatus::Pending);
+    }
+
+    Ok(CiStatus::Passing)
+}
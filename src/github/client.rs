@@ -0,0 +1,91 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use octocrab::models::{AppId, InstallationId};
+use octocrab::Octocrab;
+
+use crate::error::Error;
+
+const TOKEN_VAR: &str = "GITHUB_TOKEN";
+const APP_ID_VAR: &str = "GITHUB_APP_ID";
+const APP_PRIVATE_KEY_FILE_VAR: &str = "GITHUB_APP_PRIVATE_KEY_FILE";
+const APP_INSTALLATION_ID_VAR: &str = "GITHUB_APP_INSTALLATION_ID";
+
+fn env_var(var: &str) -> Option<String> {
+    std::env::var(var).ok().filter(|s| !s.is_empty())
+}
+
+fn env_var_u64(var: &str) -> Result<u64, Error> {
+    let Some(value) = env_var(var) else {
+        return Err(Error::GithubCredentialsMissing(var.to_string()));
+    };
+    match value.trim().parse() {
+        Ok(n) => Ok(n),
+        Err(_) => Err(Error::GithubCredentialsInvalid(var.to_string(), value)),
+    }
+}
+
+/// Builds the Github client from credentials in the environment.
+///
+/// A personal access token in `GITHUB_TOKEN` is used if present. Otherwise, if
+/// `GITHUB_APP_ID` is set, the client authenticates as an installation of that
+/// Github App, with the private key read from the PEM file at
+/// `GITHUB_APP_PRIVATE_KEY_FILE` and the installation from
+/// `GITHUB_APP_INSTALLATION_ID`. With neither, access to Github is anonymous
+/// and heavily rate limited.
+pub fn new_client() -> Result<Arc<Octocrab>, Error> {
+    if let Some(token) = env_var(TOKEN_VAR) {
+        println!("Using Github token from {}", TOKEN_VAR);
+        return match Octocrab::builder().personal_token(token).build() {
+            Ok(octo) => Ok(Arc::new(octo)),
+            Err(e) => Err(Error::GithubClientFailed(e)),
+        };
+    }
+
+    if env_var(APP_ID_VAR).is_some() {
+        let app_id = AppId(env_var_u64(APP_ID_VAR)?);
+        let installation_id = InstallationId(env_var_u64(APP_INSTALLATION_ID_VAR)?);
+
+        let Some(key_path) = env_var(APP_PRIVATE_KEY_FILE_VAR).map(PathBuf::from) else {
+            return Err(Error::GithubCredentialsMissing(
+                APP_PRIVATE_KEY_FILE_VAR.to_string(),
+            ));
+        };
+        let pem = match std::fs::read(&key_path) {
+            Ok(pem) => pem,
+            Err(io) => return Err(Error::IoError(Some(key_path), io)),
+        };
+        let key = match jsonwebtoken::EncodingKey::from_rsa_pem(&pem) {
+            Ok(key) => key,
+            Err(e) => {
+                return Err(Error::GithubCredentialsInvalid(
+                    APP_PRIVATE_KEY_FILE_VAR.to_string(),
+                    format!("{} ({})", key_path.display(), e),
+                ))
+            }
+        };
+
+        println!(
+            "Using Github App {} with installation {}",
+            app_id, installation_id
+        );
+        let app = match Octocrab::builder().app(app_id, key).build() {
+            Ok(app) => app,
+            Err(e) => return Err(Error::GithubClientFailed(e)),
+        };
+        return match app.installation(installation_id) {
+            Ok(octo) => Ok(Arc::new(octo)),
+            Err(e) => Err(Error::GithubClientFailed(e)),
+        };
+    }
+
+    eprintln!(
+        "WARNING: No Github credentials in {} or {}, using anonymous access",
+        TOKEN_VAR, APP_ID_VAR
+    );
+    Ok(octocrab::instance())
+}
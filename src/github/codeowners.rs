@@ -0,0 +1,121 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::sync::Arc;
+
+use octocrab::Octocrab;
+
+use super::paths::PathPattern;
+use crate::error::Error;
+
+/// The places that Github looks for a CODEOWNERS file, in order.
+const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// One line of a CODEOWNERS file.
+#[derive(Debug)]
+struct Rule {
+    pattern: PathPattern,
+    owners: Vec<String>,
+}
+
+/// Who owns which files in a repository, from its CODEOWNERS file.
+#[derive(Default, Debug)]
+pub struct CodeOwners {
+    rules: Vec<Rule>,
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace().take_while(|w| !w.starts_with('#'));
+        let pattern = PathPattern::parse(words.next()?);
+        let owners = words.map(str::to_string).collect();
+        Some(Rule { pattern, owners })
+    }
+}
+
+impl CodeOwners {
+    pub fn parse(text: &str) -> Self {
+        CodeOwners {
+            rules: text.lines().filter_map(Rule::parse).collect(),
+        }
+    }
+
+    /// The owners of the file at `path`, as they are written in CODEOWNERS:
+    /// `@user`, `@org/team` or an email address. As on Github, the last rule
+    /// that matches wins, even if it has no owners.
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.pattern.matches(path))
+            .map(|rule| rule.owners.as_slice())
+            .unwrap_or_default()
+    }
+}
+
+/// Fetches and parses the repository's CODEOWNERS file, or returns `None` if
+/// it doesn't have one.
+pub(super) async fn get_code_owners(
+    octo: Arc<Octocrab>,
+    repo_owner: &str,
+    repo_name: &str,
+) -> Result<Option<CodeOwners>, Error> {
+    for path in CODEOWNERS_PATHS {
+        let content_task = octo
+            .repos(repo_owner, repo_name)
+            .get_content()
+            .path(path)
+            .send()
+            .await;
+        match content_task {
+            Ok(mut content) => {
+                let text = content
+                    .take_items()
+                    .first()
+                    .and_then(|c| c.decoded_content());
+                if let Some(text) = text {
+                    return Ok(Some(CodeOwners::parse(&text)));
+                }
+            }
+            Err(octocrab::Error::GitHub { source, .. })
+                if source.status_code == http::StatusCode::NOT_FOUND => {}
+            Err(e) => return Err(Error::FailedToGetCodeOwners(e)),
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn owners() {
+        let code_owners = CodeOwners::parse(
+            "# The fallback.\n\
+            *             @carbon-language/leads\n\
+            *.md          @docs-owner  # Trailing comment.\n\
+            /toolchain/   @carbon-language/toolchain\n\
+            lexer/        @lexer-owner lexer@example.com\n\
+            /toolchain/**/BUILD @build-owner\n\
+            /toolchain/generated\n",
+        );
+        let owners_of = |path| code_owners.owners_of(path).to_vec();
+
+        assert_eq!(owners_of("README.md"), ["@docs-owner"]);
+        assert_eq!(owners_of("docs/design/README.md"), ["@docs-owner"]);
+        assert_eq!(owners_of("LICENSE"), ["@carbon-language/leads"]);
+        assert_eq!(
+            owners_of("toolchain/parse/tree.cpp"),
+            ["@carbon-language/toolchain"]
+        );
+        assert_eq!(
+            owners_of("toolchain/lex/lexer/numbers.cpp"),
+            ["@lexer-owner", "lexer@example.com"]
+        );
+        assert_eq!(owners_of("toolchain/parse/BUILD"), ["@build-owner"]);
+        // The last matching rule wins, even without owners.
+        assert!(owners_of("toolchain/generated/parser.cpp").is_empty());
+    }
+}
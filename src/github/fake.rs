@@ -0,0 +1,429 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::future::{BoxFuture, FutureExt};
+use futures::stream::{self, StreamExt};
+use octocrab::models::issues::Issue;
+use octocrab::models::pulls::{PullRequest, ReviewState};
+use serde::Deserialize;
+use serde_json::json;
+
+use super::checks::CiStatus;
+use super::codeowners::CodeOwners;
+use super::rate_limit::RateLimit;
+use super::reviews::{LastReview, ReviewRequests};
+use super::source::{GithubSource, ItemStream};
+use super::webhook::WebhookItem;
+use crate::error::Error;
+use crate::model;
+
+/// A review in a fixture, already reduced to what `LastReview` records.
+#[derive(Deserialize)]
+struct FixtureReview {
+    user: String,
+    state: ReviewState,
+    #[serde(default)]
+    submitted_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    commit_id: Option<String>,
+    #[serde(default)]
+    commits_since: usize,
+}
+
+/// A `review_requested` event in a PR's timeline, from either a user or a
+/// team.
+#[derive(Deserialize)]
+struct FixtureReviewRequest {
+    #[serde(default)]
+    reviewer: Option<String>,
+    #[serde(default)]
+    team: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize)]
+struct FixturePr {
+    number: u64,
+    title: String,
+    author: String,
+    #[serde(default)]
+    draft: bool,
+    #[serde(default)]
+    created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    head_sha: String,
+    #[serde(default)]
+    requested_reviewers: Vec<String>,
+    #[serde(default)]
+    requested_teams: Vec<String>,
+    #[serde(default)]
+    review_requests: Vec<FixtureReviewRequest>,
+    #[serde(default)]
+    reviews: Vec<FixtureReview>,
+    #[serde(default)]
+    ci: CiStatus,
+    /// The paths of the files changed by the PR.
+    #[serde(default)]
+    files: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct FixtureIssue {
+    number: u64,
+    title: String,
+    author: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    assignees: Vec<String>,
+}
+
+/// An issue or PR found by a search in a fixture.
+#[derive(Deserialize)]
+struct FixtureSearchResult {
+    /// The repository, as `owner/name`.
+    repo: String,
+    #[serde(flatten)]
+    issue: FixtureIssue,
+}
+
+#[derive(Deserialize, Default)]
+struct FixtureRepo {
+    #[serde(default)]
+    prs: Vec<FixturePr>,
+    #[serde(default)]
+    issues: Vec<FixtureIssue>,
+    /// The contents of the repository's CODEOWNERS file.
+    #[serde(default)]
+    codeowners: Option<String>,
+}
+
+/// The contents of a fixture file. Repositories are keyed by `owner/name` and
+/// teams by `org/slug`.
+#[derive(Deserialize, Default)]
+struct Fixture {
+    #[serde(default)]
+    repos: HashMap<String, FixtureRepo>,
+    #[serde(default)]
+    teams: HashMap<String, Vec<String>>,
+    /// The results of searches, keyed by the query as it is sent to Github.
+    #[serde(default)]
+    searches: HashMap<String, Vec<FixtureSearchResult>>,
+}
+
+/// An in-memory view of Github, described by a JSON fixture. It allows running
+/// the bot, or its tests, without access to Github.
+pub struct FakeGithub {
+    path: PathBuf,
+    fixture: Fixture,
+}
+
+fn author_json(login: &str) -> serde_json::Value {
+    let url = format!("https://api.github.com/users/{}", login);
+    json!({
+        "login": login,
+        "id": 1,
+        "node_id": "",
+        "avatar_url": "https://github.com/images/error/octocat_happy.gif",
+        "gravatar_id": "",
+        "url": url,
+        "html_url": format!("https://github.com/{}", login),
+        "followers_url": format!("{}/followers", url),
+        "following_url": format!("{}/following", url),
+        "gists_url": format!("{}/gists", url),
+        "starred_url": format!("{}/starred", url),
+        "subscriptions_url": format!("{}/subscriptions", url),
+        "organizations_url": format!("{}/orgs", url),
+        "repos_url": format!("{}/repos", url),
+        "events_url": format!("{}/events", url),
+        "received_events_url": format!("{}/received_events", url),
+        "type": "User",
+        "site_admin": false,
+    })
+}
+
+fn pr_json(repo: &str, pr: &FixturePr) -> serde_json::Value {
+    let url = format!("https://api.github.com/repos/{}/pulls/{}", repo, pr.number);
+    let teams: Vec<_> = pr
+        .requested_teams
+        .iter()
+        .map(|slug| {
+            json!({
+                "name": slug,
+                "slug": slug,
+                "privacy": "closed",
+                "permission": "pull",
+                "members_url": format!("https://api.github.com/teams/{}/members", slug),
+                "repositories_url": format!("https://api.github.com/teams/{}/repos", slug),
+            })
+        })
+        .collect();
+    json!({
+        "url": url,
+        "id": pr.number,
+        "number": pr.number,
+        "state": "open",
+        "title": pr.title,
+        "user": author_json(&pr.author),
+        "draft": pr.draft,
+        "created_at": pr.created_at,
+        "requested_reviewers": pr.requested_reviewers.iter().map(|r| author_json(r)).collect::<Vec<_>>(),
+        "requested_teams": teams,
+        "head": { "ref": format!("pr-{}", pr.number), "sha": pr.head_sha },
+        "base": { "ref": "trunk", "sha": "" },
+    })
+}
+
+fn issue_json(repo: &str, issue: &FixtureIssue) -> serde_json::Value {
+    let repo_url = format!("https://api.github.com/repos/{}", repo);
+    let url = format!("{}/issues/{}", repo_url, issue.number);
+    let labels: Vec<_> = issue
+        .labels
+        .iter()
+        .map(|name| {
+            json!({
+                "id": 1,
+                "node_id": "",
+                "url": format!("{}/labels/{}", repo_url, name),
+                "name": name,
+                "color": "ffffff",
+                "default": false,
+            })
+        })
+        .collect();
+    json!({
+        "id": issue.number,
+        "node_id": "",
+        "url": url,
+        "repository_url": repo_url,
+        "labels_url": format!("{}/labels", url),
+        "comments_url": format!("{}/comments", url),
+        "events_url": format!("{}/events", url),
+        "html_url": format!("https://github.com/{}/issues/{}", repo, issue.number),
+        "number": issue.number,
+        "state": "open",
+        "title": issue.title,
+        "user": author_json(&issue.author),
+        "body": issue.body,
+        "labels": labels,
+        "assignees": issue.assignees.iter().map(|a| author_json(a)).collect::<Vec<_>>(),
+        "author_association": "MEMBER",
+        "locked": false,
+        "comments": 0,
+        "created_at": "2025-01-01T00:00:00Z",
+        "updated_at": "2025-01-01T00:00:00Z",
+    })
+}
+
+impl FakeGithub {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let path = path.to_path_buf();
+        let data = match std::fs::read_to_string(&path) {
+            Ok(data) => data,
+            Err(io) => return Err(Error::IoError(Some(path), io)),
+        };
+        match serde_json::from_str(&data) {
+            Ok(fixture) => Ok(Self { path, fixture }),
+            Err(e) => Err(Error::FixtureParsingError(path, e.to_string())),
+        }
+    }
+
+    fn fixture_error(&self, e: serde_json::Error) -> Error {
+        Error::FixtureParsingError(self.path.clone(), e.to_string())
+    }
+
+    fn repo(&self, repo_owner: &str, repo_name: &str) -> (String, Option<&FixtureRepo>) {
+        let key = format!("{}/{}", repo_owner, repo_name);
+        let repo = self.fixture.repos.get(&key);
+        (key, repo)
+    }
+
+    fn pr(&self, repo_owner: &str, repo_name: &str, pr_number: u64) -> Option<&FixturePr> {
+        let (_, repo) = self.repo(repo_owner, repo_name);
+        repo?.prs.iter().find(|pr| pr.number == pr_number)
+    }
+}
+
+impl GithubSource for FakeGithub {
+    fn list_open_prs<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+    ) -> BoxFuture<'a, Result<ItemStream<PullRequest>, Error>> {
+        let (key, repo) = self.repo(repo_owner, repo_name);
+        let prs: Result<Vec<PullRequest>, Error> = repo
+            .map(|r| r.prs.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|pr| serde_json::from_value(pr_json(&key, pr)).map_err(|e| self.fixture_error(e)))
+            .collect();
+        async move { Ok(stream::iter(prs?.into_iter().map(Ok)).boxed()) }.boxed()
+    }
+
+    fn list_open_issues<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        label: &'a str,
+    ) -> BoxFuture<'a, Result<ItemStream<Issue>, Error>> {
+        let (key, repo) = self.repo(repo_owner, repo_name);
+        let issues: Result<Vec<Issue>, Error> = repo
+            .map(|r| r.issues.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .filter(|issue| issue.labels.iter().any(|l| l == label))
+            .map(|issue| {
+                serde_json::from_value(issue_json(&key, issue)).map_err(|e| self.fixture_error(e))
+            })
+            .collect();
+        async move { Ok(stream::iter(issues?.into_iter().map(Ok)).boxed()) }.boxed()
+    }
+
+    fn search_issues<'a>(
+        &'a self,
+        query: &'a str,
+    ) -> BoxFuture<'a, Result<ItemStream<Issue>, Error>> {
+        let issues: Result<Vec<Issue>, Error> = self
+            .fixture
+            .searches
+            .get(query)
+            .map(|results| results.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|result| {
+                serde_json::from_value(issue_json(&result.repo, &result.issue))
+                    .map_err(|e| self.fixture_error(e))
+            })
+            .collect();
+        async move { Ok(stream::iter(issues?.into_iter().map(Ok)).boxed()) }.boxed()
+    }
+
+    fn get_last_reviews<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        pr_number: u64,
+        _updated_at: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'a, Result<HashMap<model::GithubUserName, LastReview>, Error>> {
+        let mut last_reviews = HashMap::new();
+        if let Some(pr) = self.pr(repo_owner, repo_name, pr_number) {
+            for review in &pr.reviews {
+                last_reviews.insert(
+                    model::GithubUserName::from_str(&review.user),
+                    LastReview {
+                        state: review.state,
+                        submitted_at: review.submitted_at,
+                        commit_id: review.commit_id.clone(),
+                        commits_since: review.commits_since,
+                    },
+                );
+            }
+        }
+        async move { Ok(last_reviews) }.boxed()
+    }
+
+    fn get_review_requests<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        pr_number: u64,
+        _updated_at: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'a, Result<ReviewRequests, Error>> {
+        let mut requests = ReviewRequests::default();
+        if let Some(pr) = self.pr(repo_owner, repo_name, pr_number) {
+            for request in &pr.review_requests {
+                if let Some(user) = &request.reviewer {
+                    requests
+                        .users
+                        .insert(model::GithubUserName::from_str(user), request.created_at);
+                }
+                if let Some(team) = &request.team {
+                    requests.teams.insert(team.clone(), request.created_at);
+                }
+            }
+        }
+        async move { Ok(requests) }.boxed()
+    }
+
+    fn get_code_owners<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+    ) -> BoxFuture<'a, Result<Option<Arc<CodeOwners>>, Error>> {
+        let (_, repo) = self.repo(repo_owner, repo_name);
+        let code_owners = repo
+            .and_then(|r| r.codeowners.as_deref())
+            .map(|text| Arc::new(CodeOwners::parse(text)));
+        async move { Ok(code_owners) }.boxed()
+    }
+
+    fn list_pr_files<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        pr_number: u64,
+        _head_sha: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>, Error>> {
+        let files = self
+            .pr(repo_owner, repo_name, pr_number)
+            .map(|pr| pr.files.clone())
+            .unwrap_or_default();
+        async move { Ok(files) }.boxed()
+    }
+
+    fn get_ci_status<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        sha: &'a str,
+    ) -> BoxFuture<'a, Result<CiStatus, Error>> {
+        let (_, repo) = self.repo(repo_owner, repo_name);
+        let ci = repo
+            .and_then(|r| r.prs.iter().find(|pr| pr.head_sha == sha))
+            .map(|pr| pr.ci)
+            .unwrap_or_default();
+        async move { Ok(ci) }.boxed()
+    }
+
+    fn get_team_members<'a>(
+        &'a self,
+        org: &'a str,
+        team_slug: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<model::GithubUserName>, Error>> {
+        let key = format!("{}/{}", org, team_slug);
+        let members = self
+            .fixture
+            .teams
+            .get(&key)
+            .map(|members| {
+                members
+                    .iter()
+                    .map(|m| model::GithubUserName::from_str(m))
+                    .collect()
+            })
+            .unwrap_or_default();
+        async move { Ok(members) }.boxed()
+    }
+
+    fn repo_changed(&self, _repo_owner: &str, _repo_name: &str) {}
+
+    fn apply_change(&self, _repo_owner: &str, _repo_name: &str, _item: &WebhookItem) {}
+
+    fn prune_caches(&self) {}
+
+    fn rate_limit(&self) -> RateLimit {
+        RateLimit::default()
+    }
+
+    fn refresh_rate_limit(&self) -> BoxFuture<'_, Result<RateLimit, Error>> {
+        async move { Ok(RateLimit::default()) }.boxed()
+    }
+}
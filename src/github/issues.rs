@@ -2,91 +2,123 @@
 // Exceptions. See /LICENSE for license information.
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 
+use std::sync::Arc;
+
+use futures::stream::{Stream, TryStreamExt};
 use octocrab::models::issues::Issue;
-use octocrab::params::issues::Sort;
-use octocrab::params::Direction;
-use octocrab::params::State;
 
+use super::source::{GithubSource, ItemStream};
 use crate::error::Error;
 use crate::model;
 
 const GITHUB_URL_BASE: &str = "https://github.com";
-const LABEL_LEADS_ISSUE: &str = "leads question";
-const LABEL_BACKGROUND: &str = "long term issue";
-const LABEL_BLOCKED: &str = "blocking work";
 
-fn github_issue_url(cfg: &model::GuildConfig, number: u64) -> String {
+fn github_issue_url(repo: &model::RepoConfig, number: u64) -> String {
     format!(
         "{}/{}/{}/pull/{}",
-        GITHUB_URL_BASE, cfg.repo_owner, cfg.repo_name, number,
+        GITHUB_URL_BASE, repo.owner, repo.name, number,
     )
 }
 
+/// The open leads issues in a repository. Pages after the first are fetched
+/// from Github as the issues are consumed.
 pub struct LeadsIssueState {
-    iter: std::vec::IntoIter<Issue>,
-}
-
-pub async fn get_leads_issues(repo_owner: &str, repo_name: &str) -> Result<LeadsIssueState, Error> {
-    let labels = vec![LABEL_LEADS_ISSUE.to_string()];
-
-    // TODO: This returns an iterator over the first page (first 100) issues only.
-    let octo = octocrab::instance();
-    let issue_handler = octo.issues(repo_owner, repo_name);
-    let issues_builder = issue_handler
-        .list()
-        .state(State::Open)
-        .labels(&labels)
-        .per_page(100)
-        .sort(Sort::Updated)
-        .direction(Direction::Ascending);
-    match issues_builder.send().await {
-        Ok(bugs) => Ok(LeadsIssueState {
-            iter: bugs.into_iter(),
-        }),
-        Err(e) => Err(Error::FailedToGetIssues(e)),
-    }
+    repo: model::RepoConfig,
+    stream: ItemStream<Issue>,
 }
 
-#[derive(PartialEq, Eq)]
-pub enum Urgency {
-    Blocked,
-    Normal,
-    Background,
+pub async fn get_leads_issues(
+    github: Arc<dyn GithubSource>,
+    repo: &model::RepoConfig,
+    labels: &model::IssueLabels,
+) -> Result<LeadsIssueState, Error> {
+    Ok(LeadsIssueState {
+        repo: repo.clone(),
+        stream: github
+            .list_open_issues(&repo.owner, &repo.name, &labels.leads)
+            .await?,
+    })
 }
 
 pub struct LeadsIssue {
     pub github_issue: Issue,
+    /// Identifies the issue across repositories, as `owner/name#number`.
+    pub key: String,
+    /// The repository that the issue is in.
+    pub repo: model::RepoConfig,
     pub url: String,
-    pub urgency: Urgency,
+    pub urgency: model::Urgency,
+    /// The leads to ping about the issue, at its urgency. These are the leads
+    /// that are assigned to or mentioned in the issue, or every lead if none
+    /// are.
     pub leads: Vec<model::DiscordUserId>,
+    /// The other leads, who only see the issue in their weekly report.
+    pub other_leads: Vec<model::DiscordUserId>,
+}
+
+/// The Github users that are @-mentioned in `text`. Team mentions, like
+/// `@org/team`, are left out.
+fn mentioned_users(text: &str) -> Vec<&str> {
+    let is_login_char = |c: char| c.is_ascii_alphanumeric() || c == '-';
+    let mut mentions = Vec::new();
+    let mut prev = ' ';
+    for (i, c) in text.char_indices() {
+        // An `@` in the middle of a word is part of an email address.
+        if c == '@' && !is_login_char(prev) && prev != '@' {
+            let rest = &text[i + 1..];
+            let end = rest.find(|c| !is_login_char(c)).unwrap_or(rest.len());
+            if end > 0 && !rest[end..].starts_with('/') {
+                mentions.push(&rest[..end]);
+            }
+        }
+        prev = c;
+    }
+    mentions
 }
 
 pub fn filter_leads_issues_for_guild<'a>(
     issues: LeadsIssueState,
     cfg: &'a model::GuildConfig,
-) -> impl std::iter::Iterator<Item = LeadsIssue> + 'a {
-    issues.iter.map(|issue| -> LeadsIssue {
-        let mut urgency = Urgency::Normal;
-        for label in &issue.labels {
-            if label.name == LABEL_BLOCKED {
-                urgency = Urgency::Blocked;
-            }
-            if label.name == LABEL_BACKGROUND && urgency != Urgency::Blocked {
-                urgency = Urgency::Background;
-            }
+) -> impl Stream<Item = Result<LeadsIssue, Error>> + 'a {
+    let LeadsIssueState { repo, stream } = issues;
+    stream.map_ok(move |issue| -> LeadsIssue {
+        let urgency = cfg
+            .issue_labels
+            .urgency_of(issue.labels.iter().map(|label| label.name.as_str()));
+        // Leads that are assigned or mentioned are the ones to ping.
+        let mut involved: Vec<&str> = issue.assignees.iter().map(|a| a.login.as_str()).collect();
+        if let Some(body) = &issue.body {
+            involved.extend(mentioned_users(body));
         }
         let mut leads = Vec::new();
+        let mut other_leads = Vec::new();
         for (discord_user_id, user_config) in &cfg.users {
-            if user_config.lead {
+            if !user_config.lead {
+                continue;
+            }
+            let is_involved = user_config.github_names.iter().any(|name| {
+                involved
+                    .iter()
+                    .any(|login| login.eq_ignore_ascii_case(&name.0))
+            });
+            if is_involved {
                 leads.push(discord_user_id.clone());
+            } else {
+                other_leads.push(discord_user_id.clone());
             }
         }
+        if leads.is_empty() {
+            leads = std::mem::take(&mut other_leads);
+        }
 
         LeadsIssue {
-            url: github_issue_url(cfg, issue.number),
+            key: format!("{}#{}", repo, issue.number),
+            url: github_issue_url(&repo, issue.number),
             github_issue: issue,
+            repo: repo.clone(),
             urgency,
             leads,
+            other_leads,
         }
     })
 }
@@ -2,8 +2,32 @@
 // Exceptions. See /LICENSE for license information.
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 
+mod cache;
+pub mod checks;
+mod client;
+pub mod codeowners;
+mod fake;
 pub mod issues;
+mod octocrab_source;
+mod pages;
+pub mod paths;
 pub mod prs;
+pub mod rate_limit;
+pub mod reviews;
+pub mod search;
+pub mod source;
+pub mod teams;
+pub mod webhook;
 
+pub use checks::*;
+pub use client::*;
+pub use fake::*;
 pub use issues::*;
+pub use octocrab_source::*;
 pub use prs::*;
+pub use rate_limit::*;
+pub use reviews::*;
+pub use search::*;
+pub use source::*;
+pub use teams::*;
+pub use webhook::*;
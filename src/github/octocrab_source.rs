@@ -0,0 +1,451 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::future::{BoxFuture, FutureExt};
+use octocrab::models::issues::Issue;
+use octocrab::models::pulls::PullRequest;
+use octocrab::models::IssueState;
+use octocrab::Octocrab;
+
+use super::cache::PageCache;
+use super::checks::{self, CiStatus};
+use super::codeowners::{self, CodeOwners};
+use super::paths;
+use super::rate_limit::{RateLimit, RateLimitTracker};
+use super::reviews::{self, LastReview, ReviewRequests};
+use super::source::{GithubSource, ItemStream};
+use super::teams;
+use super::webhook::WebhookItem;
+use crate::error::Error;
+use crate::model;
+
+/// How long a repository's CODEOWNERS file is used before it is fetched again.
+/// It changes rarely, and changes to it don't wake up the bot.
+const CODE_OWNERS_FRESH_FOR: Duration = Duration::from_secs(60 * 60);
+
+/// How long the pages of a list are kept after they were last asked for. This
+/// is longer than a week, so that weekly searches are still revalidated with
+/// their ETags.
+const UNUSED_PAGES_KEPT_FOR: Duration = Duration::from_secs(8 * 24 * 60 * 60);
+
+/// The most pages of search results that are kept. Users can search for
+/// anything, so the searches are not bounded by the configured repositories.
+const MAX_SEARCH_PAGES: usize = 200;
+
+/// A repository's CODEOWNERS, or `None` if it has none, and when it was
+/// fetched.
+struct CachedCodeOwners {
+    fetched_at: Instant,
+    code_owners: Option<Arc<CodeOwners>>,
+}
+
+/// How long a settled CI result for a commit is used before it is fetched
+/// again, to pick up checks that are run again. Pending results are always
+/// fetched again.
+const CI_STATUS_FRESH_FOR: Duration = Duration::from_secs(30 * 60);
+
+/// Something looked up for a PR, as of `version`: the head SHA for its files,
+/// or when it was last updated for its reviews.
+struct CachedPrDetail<T> {
+    version: String,
+    value: T,
+}
+
+/// Details of PRs, keyed by `owner/name#number`.
+type PrDetailCache<T> = Mutex<HashMap<String, CachedPrDetail<T>>>;
+
+/// Returns the cached detail of the PR `key`, if it is still for `version`.
+fn cached_pr_detail<T: Clone>(cache: &PrDetailCache<T>, key: &str, version: &str) -> Option<T> {
+    cache
+        .lock()
+        .unwrap()
+        .get(key)
+        .filter(|cached| cached.version == version)
+        .map(|cached| cached.value.clone())
+}
+
+fn cache_pr_detail<T>(cache: &PrDetailCache<T>, key: String, version: String, value: T) {
+    cache
+        .lock()
+        .unwrap()
+        .insert(key, CachedPrDetail { version, value });
+}
+
+/// Drops the details of the PRs that aren't in `keys`.
+fn retain_pr_details<T>(cache: &PrDetailCache<T>, keys: &HashSet<String>) {
+    cache.lock().unwrap().retain(|key, _| keys.contains(key));
+}
+
+/// The CI result for a commit, and when it was fetched.
+struct CachedCiStatus {
+    fetched_at: Instant,
+    ci: CiStatus,
+}
+
+/// Talks to the real Github through octocrab.
+pub struct OctocrabSource {
+    octo: Arc<Octocrab>,
+    prs: Arc<PageCache<PullRequest>>,
+    issues: Arc<PageCache<Issue>>,
+    search: Arc<PageCache<Issue>>,
+    /// The CODEOWNERS of each repository, keyed by `owner/name`.
+    code_owners: Mutex<HashMap<String, CachedCodeOwners>>,
+    /// The files changed by each PR, as of its head SHA.
+    pr_files: PrDetailCache<Vec<String>>,
+    /// The last reviews of each PR, as of when it was last updated.
+    last_reviews: PrDetailCache<HashMap<model::GithubUserName, LastReview>>,
+    /// The review requests of each PR, as of when it was last updated.
+    review_requests: PrDetailCache<ReviewRequests>,
+    /// The settled CI results, keyed by `owner/name@sha`.
+    ci_statuses: Mutex<HashMap<String, CachedCiStatus>>,
+    rate_limit: Arc<RateLimitTracker>,
+}
+
+impl OctocrabSource {
+    pub fn new(octo: Arc<Octocrab>) -> Self {
+        let rate_limit = Arc::new(RateLimitTracker::default());
+        Self {
+            octo,
+            prs: PageCache::new(rate_limit.clone()),
+            issues: PageCache::new(rate_limit.clone()),
+            search: PageCache::new(rate_limit.clone()),
+            code_owners: Mutex::new(HashMap::new()),
+            pr_files: Mutex::new(HashMap::new()),
+            last_reviews: Mutex::new(HashMap::new()),
+            review_requests: Mutex::new(HashMap::new()),
+            ci_statuses: Mutex::new(HashMap::new()),
+            rate_limit,
+        }
+    }
+
+    /// Notes when a request failed because of the rate limit. Unlike list
+    /// pages, octocrab doesn't show us the response headers for these, so the
+    /// quota is looked up to find out when it resets.
+    async fn track<T>(&self, result: Result<T, Error>) -> Result<T, Error> {
+        if let Err(e) = &result {
+            if e.is_github_rate_limited() {
+                // Failing to refresh just leaves the quota unknown.
+                let _ = self.refresh_rate_limit().await;
+                self.rate_limit.record_limited();
+            }
+        }
+        result
+    }
+}
+
+/// The label that a list of issues is filtered by, from the URL of its first
+/// page.
+fn list_label(first_url: &str) -> Option<String> {
+    let (_, query) = first_url.split_once('?')?;
+    form_urlencoded::parse(query.as_bytes())
+        .find(|(key, _)| key == "labels")
+        .map(|(_, label)| label.into_owned())
+}
+
+/// The query for an open list, least recently updated first.
+fn open_list_query(label: Option<&str>) -> String {
+    let mut query = form_urlencoded::Serializer::new(String::new());
+    query.append_pair("state", "open");
+    if let Some(label) = label {
+        query.append_pair("labels", label);
+    }
+    query
+        .append_pair("sort", "updated")
+        .append_pair("direction", "asc")
+        .append_pair("per_page", "100")
+        .finish()
+}
+
+impl GithubSource for OctocrabSource {
+    fn list_open_prs<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+    ) -> BoxFuture<'a, Result<ItemStream<PullRequest>, Error>> {
+        let url = format!(
+            "/repos/{}/{}/pulls?{}",
+            repo_owner,
+            repo_name,
+            open_list_query(None)
+        );
+        let stream = self
+            .prs
+            .clone()
+            .stream(self.octo.clone(), url, Error::FailedToGetPRs);
+        async move { Ok(stream) }.boxed()
+    }
+
+    fn list_open_issues<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        label: &'a str,
+    ) -> BoxFuture<'a, Result<ItemStream<Issue>, Error>> {
+        let url = format!(
+            "/repos/{}/{}/issues?{}",
+            repo_owner,
+            repo_name,
+            open_list_query(Some(label))
+        );
+        let stream = self
+            .issues
+            .clone()
+            .stream(self.octo.clone(), url, Error::FailedToGetIssues);
+        async move { Ok(stream) }.boxed()
+    }
+
+    fn search_issues<'a>(
+        &'a self,
+        query: &'a str,
+    ) -> BoxFuture<'a, Result<ItemStream<Issue>, Error>> {
+        let url = format!(
+            "/search/issues?{}",
+            form_urlencoded::Serializer::new(String::new())
+                .append_pair("q", query)
+                .append_pair("per_page", "100")
+                .finish()
+        );
+        let stream = self
+            .search
+            .clone()
+            .stream(self.octo.clone(), url, Error::FailedToSearch);
+        async move { Ok(stream) }.boxed()
+    }
+
+    fn get_last_reviews<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        pr_number: u64,
+        updated_at: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'a, Result<HashMap<model::GithubUserName, LastReview>, Error>> {
+        async move {
+            // Reviews and pushes both update the PR.
+            let key = format!("{}/{}#{}", repo_owner, repo_name, pr_number);
+            let version = updated_at.map(|t| t.to_rfc3339());
+            if let Some(version) = &version {
+                if let Some(last_reviews) = cached_pr_detail(&self.last_reviews, &key, version) {
+                    return Ok(last_reviews);
+                }
+            }
+            let result =
+                reviews::get_last_reviews(self.octo.clone(), repo_owner, repo_name, pr_number)
+                    .await;
+            let last_reviews = self.track(result).await?;
+            if let Some(version) = version {
+                cache_pr_detail(&self.last_reviews, key, version, last_reviews.clone());
+            }
+            Ok(last_reviews)
+        }
+        .boxed()
+    }
+
+    fn get_review_requests<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        pr_number: u64,
+        updated_at: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'a, Result<ReviewRequests, Error>> {
+        async move {
+            // Requesting a review updates the PR.
+            let key = format!("{}/{}#{}", repo_owner, repo_name, pr_number);
+            let version = updated_at.map(|t| t.to_rfc3339());
+            if let Some(version) = &version {
+                if let Some(requests) = cached_pr_detail(&self.review_requests, &key, version) {
+                    return Ok(requests);
+                }
+            }
+            let result =
+                reviews::get_review_requests(self.octo.clone(), repo_owner, repo_name, pr_number)
+                    .await;
+            let requests = self.track(result).await?;
+            if let Some(version) = version {
+                cache_pr_detail(&self.review_requests, key, version, requests.clone());
+            }
+            Ok(requests)
+        }
+        .boxed()
+    }
+
+    fn get_code_owners<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+    ) -> BoxFuture<'a, Result<Option<Arc<CodeOwners>>, Error>> {
+        async move {
+            let key = format!("{}/{}", repo_owner, repo_name);
+            if let Some(cached) = self.code_owners.lock().unwrap().get(&key) {
+                if cached.fetched_at.elapsed() < CODE_OWNERS_FRESH_FOR {
+                    return Ok(cached.code_owners.clone());
+                }
+            }
+            let result = codeowners::get_code_owners(self.octo.clone(), repo_owner, repo_name)
+                .await
+                .map(|code_owners| code_owners.map(Arc::new));
+            let code_owners = self.track(result).await?;
+            self.code_owners.lock().unwrap().insert(
+                key,
+                CachedCodeOwners {
+                    fetched_at: Instant::now(),
+                    code_owners: code_owners.clone(),
+                },
+            );
+            Ok(code_owners)
+        }
+        .boxed()
+    }
+
+    fn list_pr_files<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        pr_number: u64,
+        head_sha: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>, Error>> {
+        async move {
+            // The files only change when commits are pushed to the PR.
+            let key = format!("{}/{}#{}", repo_owner, repo_name, pr_number);
+            if let Some(files) = cached_pr_detail(&self.pr_files, &key, head_sha) {
+                return Ok(files);
+            }
+            let result =
+                paths::list_pr_files(self.octo.clone(), repo_owner, repo_name, pr_number).await;
+            let files = self.track(result).await?;
+            cache_pr_detail(&self.pr_files, key, head_sha.to_string(), files.clone());
+            Ok(files)
+        }
+        .boxed()
+    }
+
+    fn get_ci_status<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        sha: &'a str,
+    ) -> BoxFuture<'a, Result<CiStatus, Error>> {
+        async move {
+            let key = format!("{}/{}@{}", repo_owner, repo_name, sha);
+            if let Some(cached) = self.ci_statuses.lock().unwrap().get(&key) {
+                if cached.fetched_at.elapsed() < CI_STATUS_FRESH_FOR {
+                    return Ok(cached.ci);
+                }
+            }
+            let result = checks::get_ci_status(self.octo.clone(), repo_owner, repo_name, sha).await;
+            let ci = self.track(result).await?;
+            if matches!(ci, CiStatus::Passing | CiStatus::Failing) {
+                self.ci_statuses.lock().unwrap().insert(
+                    key,
+                    CachedCiStatus {
+                        fetched_at: Instant::now(),
+                        ci,
+                    },
+                );
+            }
+            Ok(ci)
+        }
+        .boxed()
+    }
+
+    fn get_team_members<'a>(
+        &'a self,
+        org: &'a str,
+        team_slug: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<model::GithubUserName>, Error>> {
+        async move {
+            let result = teams::get_team_members(self.octo.clone(), org, team_slug).await;
+            self.track(result).await
+        }
+        .boxed()
+    }
+
+    fn repo_changed(&self, repo_owner: &str, repo_name: &str) {
+        let prefix = format!("/repos/{}/{}/", repo_owner, repo_name);
+        self.prs.expire(&prefix);
+        self.issues.expire(&prefix);
+    }
+
+    fn apply_change(&self, repo_owner: &str, repo_name: &str, item: &WebhookItem) {
+        let prefix = format!("/repos/{}/{}/", repo_owner, repo_name);
+        match item {
+            WebhookItem::Pr(pr) => {
+                let open = pr.state == Some(IssueState::Open);
+                self.prs.update(
+                    &prefix,
+                    |cached| cached.number == pr.number,
+                    |_| open.then(|| (**pr).clone()),
+                );
+            }
+            WebhookItem::Issue(issue) => {
+                let open = issue.state == IssueState::Open;
+                self.issues.update(
+                    &prefix,
+                    |cached| cached.number == issue.number,
+                    |first_url| {
+                        let label = list_label(first_url)?;
+                        let labeled = issue
+                            .labels
+                            .iter()
+                            .any(|l| l.name.eq_ignore_ascii_case(&label));
+                        (open && labeled).then(|| (**issue).clone())
+                    },
+                );
+            }
+        }
+    }
+
+    fn prune_caches(&self) {
+        self.prs.prune(UNUSED_PAGES_KEPT_FOR);
+        self.issues.prune(UNUSED_PAGES_KEPT_FOR);
+        self.search.prune(UNUSED_PAGES_KEPT_FOR);
+        self.search.truncate(MAX_SEARCH_PAGES);
+
+        // Settled results that are no longer fresh are fetched again anyway.
+        self.code_owners
+            .lock()
+            .unwrap()
+            .retain(|_, cached| cached.fetched_at.elapsed() < CODE_OWNERS_FRESH_FOR);
+        self.ci_statuses
+            .lock()
+            .unwrap()
+            .retain(|_, cached| cached.fetched_at.elapsed() < CI_STATUS_FRESH_FOR);
+
+        // The details of PRs are only needed while they are open, which is
+        // while they are in the cached lists of open PRs.
+        let mut open_prs = HashSet::new();
+        self.prs.for_each_item(|first_url, pr| {
+            let repo = first_url
+                .strip_prefix("/repos/")
+                .and_then(|rest| rest.split_once("/pulls?"));
+            if let Some((repo, _)) = repo {
+                open_prs.insert(format!("{}#{}", repo, pr.number));
+            }
+        });
+        retain_pr_details(&self.pr_files, &open_prs);
+        retain_pr_details(&self.last_reviews, &open_prs);
+        retain_pr_details(&self.review_requests, &open_prs);
+    }
+
+    fn rate_limit(&self) -> RateLimit {
+        self.rate_limit.get()
+    }
+
+    fn refresh_rate_limit(&self) -> BoxFuture<'_, Result<RateLimit, Error>> {
+        async move {
+            match self.octo.ratelimit().get().await {
+                Ok(limits) => {
+                    self.rate_limit.record_rate(&limits.resources.core);
+                    Ok(self.rate_limit.get())
+                }
+                Err(e) => Err(Error::FailedToGetRateLimit(e)),
+            }
+        }
+        .boxed()
+    }
+}
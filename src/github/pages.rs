@@ -0,0 +1,42 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::sync::Arc;
+
+use futures::stream::{self, BoxStream, StreamExt};
+use octocrab::{Octocrab, Page};
+
+use crate::error::Error;
+
+/// Turns the first page of a Github list response into a stream over the
+/// items of every page. The next page is only requested from Github once the
+/// items of the previous page have been consumed.
+pub fn stream_pages<T>(
+    octo: Arc<Octocrab>,
+    first_page: Page<T>,
+    to_error: fn(octocrab::Error) -> Error,
+) -> BoxStream<'static, Result<T, Error>>
+where
+    T: serde::de::DeserializeOwned + Send + 'static,
+{
+    // The state is the last page seen. Once its items are taken, the page is
+    // kept around for its link to the next page.
+    stream::unfold(Some(first_page), move |page| {
+        let octo = octo.clone();
+        async move {
+            let mut page = page?;
+            if page.items.is_empty() {
+                page = match octo.get_page::<T>(&page.next).await {
+                    Ok(Some(next_page)) => next_page,
+                    Ok(None) => return None,
+                    Err(e) => return Some((vec![Err(to_error(e))], None)),
+                };
+            }
+            let items = page.take_items().into_iter().map(Ok).collect();
+            Some((items, Some(page)))
+        }
+    })
+    .flat_map(stream::iter)
+    .boxed()
+}
@@ -0,0 +1,152 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::sync::Arc;
+
+use futures::stream::TryStreamExt;
+use octocrab::models::repos::DiffEntry;
+use octocrab::{Octocrab, Page};
+
+use super::pages::stream_pages;
+use crate::error::Error;
+
+/// A pattern for paths in a repository, written like in `.gitignore` and
+/// CODEOWNERS. `*` and `?` match within a path segment, and `**` matches any
+/// number of segments.
+#[derive(Debug)]
+pub struct PathPattern {
+    segments: Vec<String>,
+    /// Whether the pattern only matches directories, when it ends with a `/`.
+    directory: bool,
+}
+
+/// Matches `text` against a glob with `*` and `?` wildcards, neither of which
+/// matches a `/`.
+fn glob_matches(glob: &[u8], text: &[u8]) -> bool {
+    match glob.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|i| glob_matches(rest, &text[i..])),
+        Some((b'?', rest)) => !text.is_empty() && glob_matches(rest, &text[1..]),
+        Some((c, rest)) => text.first() == Some(c) && glob_matches(rest, &text[1..]),
+    }
+}
+
+fn segments_match(pattern: &[String], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((segment, rest)) if segment == "**" => {
+            (0..=path.len()).any(|i| segments_match(rest, &path[i..]))
+        }
+        Some((segment, rest)) => match path.split_first() {
+            Some((name, path_rest)) => {
+                glob_matches(segment.as_bytes(), name.as_bytes()) && segments_match(rest, path_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+impl PathPattern {
+    pub fn parse(mut pattern: &str) -> Self {
+        let directory = pattern.ends_with('/');
+        pattern = pattern.trim_end_matches('/');
+        // A pattern with a `/` before its end is relative to the root of the
+        // repository, and one without matches at any depth.
+        let anchored = pattern.contains('/');
+        pattern = pattern.trim_start_matches('/');
+        let mut segments: Vec<String> = Vec::new();
+        if !anchored {
+            segments.push("**".to_string());
+        }
+        segments.extend(pattern.split('/').map(str::to_string));
+        PathPattern {
+            segments,
+            directory,
+        }
+    }
+
+    /// Whether the pattern covers the file at `path`, either directly or
+    /// through one of its directories. Like in CODEOWNERS, a pattern that ends
+    /// in a wildcard only covers the files directly in a directory, so `docs/*`
+    /// doesn't cover `docs/design/README.md`.
+    pub fn matches(&self, path: &str) -> bool {
+        let path: Vec<&str> = path.split('/').collect();
+        let ends_in_wildcard = self
+            .segments
+            .last()
+            .is_some_and(|segment| segment.contains(['*', '?']));
+        let covered = if self.directory {
+            1..path.len()
+        } else if ends_in_wildcard {
+            path.len()..path.len() + 1
+        } else {
+            1..path.len() + 1
+        };
+        covered
+            .into_iter()
+            .any(|len| segments_match(&self.segments, &path[..len]))
+    }
+}
+
+/// Lists the paths of the files changed by a PR.
+pub(super) async fn list_pr_files(
+    octo: Arc<Octocrab>,
+    repo_owner: &str,
+    repo_name: &str,
+    pr_number: u64,
+) -> Result<Vec<String>, Error> {
+    let route = format!(
+        "/repos/{}/{}/pulls/{}/files",
+        repo_owner, repo_name, pr_number
+    );
+    match octo
+        .get::<Page<DiffEntry>, _, _>(route, Some(&[("per_page", 100)]))
+        .await
+    {
+        Ok(page) => {
+            stream_pages(octo.clone(), page, Error::FailedToGetPrFiles)
+                .map_ok(|entry| entry.filename)
+                .try_collect()
+                .await
+        }
+        Err(e) => Err(Error::FailedToGetPrFiles(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn patterns() {
+        let matches = |pattern, path| PathPattern::parse(pattern).matches(path);
+
+        assert!(matches("toolchain/check/**", "toolchain/check/eval.cpp"));
+        assert!(matches(
+            "toolchain/check/**",
+            "toolchain/check/testdata/a.carbon"
+        ));
+        assert!(!matches("toolchain/check/**", "toolchain/lower/eval.cpp"));
+        assert!(matches("toolchain/check", "toolchain/check/eval.cpp"));
+        // Anchored patterns only match from the root.
+        assert!(!matches("/check", "toolchain/check/eval.cpp"));
+        assert!(!matches("check/eval.cpp", "toolchain/check/eval.cpp"));
+        // Patterns without a `/` match at any depth.
+        assert!(matches("check", "toolchain/check/eval.cpp"));
+        assert!(matches("*.md", "docs/design/README.md"));
+        assert!(!matches("*.md", "docs/design/README.txt"));
+        assert!(matches("toolchain/**/BUILD", "toolchain/BUILD"));
+        assert!(matches("toolchain/**/BUILD", "toolchain/parse/BUILD"));
+        assert!(matches("eval.???", "toolchain/check/eval.cpp"));
+        // A directory pattern doesn't match a file of the same name.
+        assert!(matches("lexer/", "toolchain/lexer/numbers.cpp"));
+        assert!(!matches("lexer/", "docs/lexer"));
+        // A wildcard at the end only matches the files in the directory.
+        assert!(matches("docs/*", "docs/getting_started.md"));
+        assert!(!matches("docs/*", "docs/build-app/troubleshooting.md"));
+        assert!(!matches("*.md", "docs/design.md/README.txt"));
+        assert!(matches("docs/*/", "docs/build-app/troubleshooting.md"));
+        assert!(matches("*", "docs/build-app/troubleshooting.md"));
+    }
+}
@@ -2,20 +2,28 @@
 // Exceptions. See /LICENSE for license information.
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 
-use octocrab::models::pulls::PullRequest;
-use octocrab::params::pulls::Sort;
-use octocrab::params::Direction;
-use octocrab::params::State;
+use std::collections::HashMap;
+use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
+use futures::stream::{Stream, TryStreamExt};
+use octocrab::models::pulls::{PullRequest, ReviewState};
+
+use super::checks::CiStatus;
+use super::codeowners::CodeOwners;
+use super::paths::PathPattern;
+use super::reviews::{LastReview, ReviewStatus};
+use super::source::{GithubSource, ItemStream};
+use super::teams::normalize_team_slug;
 use crate::error::Error;
 use crate::model;
 
 const GITHUB_URL_BASE: &str = "https://github.com";
 
-fn github_pr_url(cfg: &model::GuildConfig, number: u64) -> String {
+fn github_pr_url(repo: &model::RepoConfig, number: u64) -> String {
     format!(
         "{}/{}/{}/pull/{}",
-        GITHUB_URL_BASE, cfg.repo_owner, cfg.repo_name, number,
+        GITHUB_URL_BASE, repo.owner, repo.name, number,
     )
 }
 
@@ -23,65 +31,459 @@ fn github_pr_url(cfg: &model::GuildConfig, number: u64) -> String {
 pub struct Reviewer {
     pub github_user: model::GithubUserName,
     pub discord_users: Vec<model::DiscordUserId>,
+    /// The slug of the Github team that the review was requested from, or
+    /// `None` if it was requested from the user directly.
+    pub team: Option<String>,
+    pub status: ReviewStatus,
+    /// When the review was last requested. This is only looked up for
+    /// registered reviewers, and is `None` for past reviewers whose review is
+    /// no longer requested.
+    pub requested_at: Option<DateTime<Utc>>,
+}
+
+impl Reviewer {
+    fn new(
+        cfg: &model::GuildConfig,
+        github_user: model::GithubUserName,
+        team: Option<String>,
+    ) -> Self {
+        let mut discord_users = Vec::new();
+        for (discord_user_id, user_config) in &cfg.users {
+            if user_config.github_names.contains(&github_user) {
+                discord_users.push(discord_user_id.clone());
+            }
+        }
+        Reviewer {
+            github_user,
+            discord_users,
+            team,
+            status: ReviewStatus::NewRequest,
+            requested_at: None,
+        }
+    }
 }
 
 pub struct Pr {
     pub github_pr: PullRequest,
+    /// Identifies the PR across repositories, as `owner/name#number`.
+    pub key: String,
+    /// The repository that the PR is in.
+    pub repo: model::RepoConfig,
     pub url: String,
+    /// The requested reviewers, and the registered users that reviewed the PR
+    /// before and haven't seen its latest commits.
     pub reviewers: Vec<Reviewer>,
+    /// The registered users who authored the PR.
+    pub authors: Vec<model::DiscordUserId>,
+    /// The last review from each Github user that has reviewed the PR. This is
+    /// only looked up for PRs that a registered user is involved in.
+    pub last_reviews: HashMap<model::GithubUserName, LastReview>,
+    /// The CI result for the head of the PR. Like `last_reviews`, this is only
+    /// looked up for PRs that a registered user is involved in.
+    pub ci: CiStatus,
+    /// The registered users that own files changed by the PR, according to
+    /// CODEOWNERS. This is only looked up for unassigned PRs.
+    pub code_owners: Vec<model::DiscordUserId>,
+    /// The registered users that watch paths changed by the PR, and aren't
+    /// already reviewing or authoring it.
+    pub watchers: Vec<model::DiscordUserId>,
+}
+
+/// Where a PR stands, from the point of view of its author.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AuthorStatus {
+    Approved,
+    ChangesRequested,
+    /// Nobody has been asked to review the PR, and nobody has.
+    NoReviewer,
+    AwaitingReview,
+}
+
+impl Pr {
+    /// The most actionable status of the reviews requested from the Discord
+    /// user, or `None` if no review is requested from them.
+    pub fn review_status_for(
+        &self,
+        discord_user_id: &model::DiscordUserId,
+    ) -> Option<ReviewStatus> {
+        self.reviewers
+            .iter()
+            .filter(|r| r.discord_users.contains(discord_user_id))
+            .map(|r| r.status)
+            .min()
+    }
+
+    /// When the Discord user was first asked for one of the reviews that are
+    /// still requested from them.
+    pub fn review_requested_at_for(
+        &self,
+        discord_user_id: &model::DiscordUserId,
+    ) -> Option<DateTime<Utc>> {
+        self.reviewers
+            .iter()
+            .filter(|r| r.discord_users.contains(discord_user_id))
+            .filter_map(|r| r.requested_at)
+            .min()
+    }
+
+    pub fn author_status(&self) -> AuthorStatus {
+        // Github drops reviewers from the requested reviewers when they review,
+        // so the ones still there have yet to review, unless they requested
+        // changes that the author hasn't answered.
+        let status_of = |github_user: &model::GithubUserName| {
+            self.reviewers
+                .iter()
+                .find(|r| r.team.is_none() && r.github_user == *github_user)
+                .map(|r| r.status)
+        };
+        let outstanding: Vec<model::GithubUserName> = self
+            .github_pr
+            .requested_reviewers
+            .iter()
+            .flatten()
+            .map(|r| r.into())
+            .filter(|github_user| status_of(github_user) != Some(ReviewStatus::WaitingOnAuthor))
+            .collect();
+        let any_team_requested = self
+            .github_pr
+            .requested_teams
+            .iter()
+            .flatten()
+            .next()
+            .is_some();
+
+        // A review still stands if its reviewer wasn't asked to look again and
+        // nothing was pushed since.
+        let any_current_review_is = |state| {
+            self.last_reviews.iter().any(|(github_user, r)| {
+                r.state == state && r.commits_since == 0 && !outstanding.contains(github_user)
+            })
+        };
+        if any_current_review_is(ReviewState::ChangesRequested) {
+            AuthorStatus::ChangesRequested
+        } else if !outstanding.is_empty() || any_team_requested {
+            AuthorStatus::AwaitingReview
+        } else if any_current_review_is(ReviewState::Approved) {
+            AuthorStatus::Approved
+        } else if self.last_reviews.is_empty() {
+            AuthorStatus::NoReviewer
+        } else {
+            AuthorStatus::AwaitingReview
+        }
+    }
+
+    /// Whether the PR is ready for review, but nobody has been asked to review
+    /// it or has reviewed it. Reviews are only looked up when the guild has
+    /// triagers or registered owners of the PR's files, who are the ones to
+    /// hear about these PRs.
+    pub fn is_unassigned(&self) -> bool {
+        !self.github_pr.draft.unwrap_or_default()
+            && self
+                .github_pr
+                .requested_reviewers
+                .iter()
+                .flatten()
+                .next()
+                .is_none()
+            && self
+                .github_pr
+                .requested_teams
+                .iter()
+                .flatten()
+                .next()
+                .is_none()
+            && self.last_reviews.is_empty()
+    }
+
+    /// When the PR was last reviewed, or opened if it has not been reviewed.
+    pub fn waiting_since(&self) -> Option<DateTime<Utc>> {
+        self.last_reviews
+            .values()
+            .filter_map(|r| r.submitted_at)
+            .max()
+            .or(self.github_pr.created_at)
+    }
 }
 
+/// The number of PRs to look up the review history and CI status for at once.
+const CONCURRENT_DETAIL_LOOKUPS: usize = 8;
+
+/// The open PRs in a repository. Pages after the first are fetched from Github
+/// as the PRs are consumed.
 pub struct PrState {
-    iter: std::vec::IntoIter<PullRequest>,
+    github: Arc<dyn GithubSource>,
+    repo: model::RepoConfig,
+    code_owners: Option<Arc<CodeOwners>>,
+    stream: ItemStream<PullRequest>,
+}
+
+pub async fn get_prs(
+    github: Arc<dyn GithubSource>,
+    repo: &model::RepoConfig,
+) -> Result<PrState, Error> {
+    Ok(PrState {
+        stream: github.list_open_prs(&repo.owner, &repo.name).await?,
+        code_owners: github.get_code_owners(&repo.owner, &repo.name).await?,
+        github,
+        repo: repo.clone(),
+    })
+}
+
+/// The registered users that an owner in CODEOWNERS stands for. Teams are
+/// expanded through the guild's `github_teams`, and email addresses are left
+/// out.
+fn discord_users_for_owner(cfg: &model::GuildConfig, owner: &str) -> Vec<model::DiscordUserId> {
+    let Some(name) = owner.strip_prefix('@') else {
+        return Vec::new();
+    };
+    let github_users = if name.contains('/') {
+        match cfg.github_teams.get(&normalize_team_slug(name)) {
+            Some(members) => members.clone(),
+            None => Vec::new(),
+        }
+    } else {
+        vec![model::GithubUserName::from_str(name)]
+    };
+    cfg.users
+        .iter()
+        .filter(|(_, user_config)| {
+            github_users
+                .iter()
+                .any(|github_user| user_config.github_names.contains(github_user))
+        })
+        .map(|(discord_user_id, _)| discord_user_id.clone())
+        .collect()
 }
 
-pub async fn get_prs(repo_owner: &str, repo_name: &str) -> Result<PrState, Error> {
-    // TODO: This returns an iterator over the first page (first 100) PRs only.
-    let prs_task = octocrab::instance()
-        .pulls(repo_owner, repo_name)
-        .list()
-        .state(State::Open)
-        .per_page(100)
-        .sort(Sort::Updated)
-        .direction(Direction::Ascending)
-        .send()
-        .await;
-    match prs_task {
-        Ok(prs) => Ok(PrState {
-            iter: prs.into_iter(),
-        }),
-        Err(e) => Err(Error::FailedToGetPRs(e)),
+async fn add_pr_details(
+    github: Arc<dyn GithubSource>,
+    mut pr: Pr,
+    cfg: &model::GuildConfig,
+    code_owners: Option<Arc<CodeOwners>>,
+) -> Result<Pr, Error> {
+    // Authors don't review their own PRs.
+    let author: Option<model::GithubUserName> =
+        pr.github_pr.user.as_ref().map(|user| user.as_ref().into());
+    let is_author = |discord_user_id: &model::DiscordUserId| {
+        author
+            .as_ref()
+            .is_some_and(|author| cfg.users[discord_user_id].github_names.contains(author))
+    };
+
+    // Users that watch paths hear about every PR that changes them, unless
+    // they already review it.
+    let mut files = None;
+    let has_watchers = cfg.users.values().any(|u| !u.watched_paths.is_empty());
+    if has_watchers && !pr.github_pr.draft.unwrap_or_default() {
+        let pr_files = github
+            .list_pr_files(
+                &pr.repo.owner,
+                &pr.repo.name,
+                pr.github_pr.number,
+                &pr.github_pr.head.sha,
+            )
+            .await?;
+        for (discord_user_id, user_config) in &cfg.users {
+            let watches = user_config.watched_paths.iter().any(|pattern| {
+                let pattern = PathPattern::parse(pattern);
+                pr_files.iter().any(|file| pattern.matches(file))
+            });
+            if watches
+                && !is_author(discord_user_id)
+                && pr.review_status_for(discord_user_id).is_none()
+            {
+                pr.watchers.push(discord_user_id.clone());
+            }
+        }
+        files = Some(pr_files);
     }
+
+    // Code owners hear about unassigned PRs that change their files. Until the
+    // reviews are looked up, these are only the PRs that nobody is requested
+    // to review.
+    if let (Some(code_owners), true) = (&code_owners, pr.is_unassigned()) {
+        let files = match files {
+            Some(files) => files,
+            None => {
+                github
+                    .list_pr_files(
+                        &pr.repo.owner,
+                        &pr.repo.name,
+                        pr.github_pr.number,
+                        &pr.github_pr.head.sha,
+                    )
+                    .await?
+            }
+        };
+        let mut owners: Vec<&String> = files
+            .iter()
+            .flat_map(|file| code_owners.owners_of(file))
+            .collect();
+        owners.sort();
+        owners.dedup();
+        for owner in owners {
+            for discord_user_id in discord_users_for_owner(cfg, owner) {
+                if !is_author(&discord_user_id) && !pr.code_owners.contains(&discord_user_id) {
+                    pr.code_owners.push(discord_user_id);
+                }
+            }
+        }
+    }
+
+    // Only registered users get reports, so don't spend requests on the rest.
+    let any_registered_reviewer = pr.reviewers.iter().any(|r| !r.discord_users.is_empty());
+    let has_triagers = cfg.users.values().any(|user_config| user_config.triager);
+    let needs_triage = pr.is_unassigned() && (has_triagers || !pr.code_owners.is_empty());
+    // Past reviewers only need a look once the head of the PR moves.
+    let has_former_reviewers = cfg.users.values().any(|user_config| {
+        user_config
+            .reviewed_prs
+            .get(&pr.key)
+            .is_some_and(|sha| *sha != pr.github_pr.head.sha)
+    });
+    if !any_registered_reviewer
+        && pr.authors.is_empty()
+        && !needs_triage
+        && pr.watchers.is_empty()
+        && !has_former_reviewers
+    {
+        return Ok(pr);
+    }
+
+    pr.last_reviews = github
+        .get_last_reviews(
+            &pr.repo.owner,
+            &pr.repo.name,
+            pr.github_pr.number,
+            pr.github_pr.updated_at,
+        )
+        .await?;
+    // Replies from the author in review threads show up as their own reviews,
+    // but they are not reviewing the PR.
+    if let Some(user) = &pr.github_pr.user {
+        pr.last_reviews.remove(&user.as_ref().into());
+    }
+    if !pr.is_unassigned() {
+        pr.code_owners.clear();
+    }
+
+    // Authors hear whether their reviewers were asked to look again.
+    let requests = if any_registered_reviewer || !pr.authors.is_empty() {
+        Some(
+            github
+                .get_review_requests(
+                    &pr.repo.owner,
+                    &pr.repo.name,
+                    pr.github_pr.number,
+                    pr.github_pr.updated_at,
+                )
+                .await?,
+        )
+    } else {
+        None
+    };
+    for r in &mut pr.reviewers {
+        let requested_at = requests.as_ref().and_then(|requests| match &r.team {
+            Some(team) => requests.teams.get(team).copied(),
+            None => requests.users.get(&r.github_user).copied(),
+        });
+        r.status =
+            ReviewStatus::from_last_review(pr.last_reviews.get(&r.github_user), requested_at);
+        if requests.is_some() {
+            // Fall back to when the PR was opened if the timeline doesn't
+            // have the request.
+            r.requested_at = requested_at.or(pr.github_pr.created_at);
+        }
+    }
+
+    // Users that reviewed the PR before hear about new commits, even once
+    // their review is no longer requested.
+    if has_former_reviewers {
+        for (discord_user_id, user_config) in &cfg.users {
+            if !user_config.reviewed_prs.contains_key(&pr.key)
+                || pr.review_status_for(discord_user_id).is_some()
+            {
+                continue;
+            }
+            let updated_for = user_config.github_names.iter().find(|name| {
+                pr.last_reviews
+                    .get(*name)
+                    .is_some_and(|review| review.commits_since > 0)
+            });
+            if let Some(github_user) = updated_for {
+                pr.reviewers.push(Reviewer {
+                    github_user: github_user.clone(),
+                    discord_users: vec![discord_user_id.clone()],
+                    team: None,
+                    status: ReviewStatus::AuthorUpdated,
+                    requested_at: None,
+                });
+                pr.watchers.retain(|id| id != discord_user_id);
+            }
+        }
+    }
+
+    pr.ci = github
+        .get_ci_status(&pr.repo.owner, &pr.repo.name, &pr.github_pr.head.sha)
+        .await?;
+    Ok(pr)
 }
 
 pub fn filter_prs_for_guild<'a>(
     prs: PrState,
     cfg: &'a model::GuildConfig,
-) -> impl std::iter::Iterator<Item = Pr> + 'a {
-    prs.iter.map(|pr| -> Pr {
+) -> impl Stream<Item = Result<Pr, Error>> + 'a {
+    let PrState {
+        github,
+        repo,
+        code_owners,
+        stream,
+    } = prs;
+
+    let prs = stream.map_ok(move |pr| -> Pr {
         let mut reviewers = Vec::new();
         if let Some(rs) = &pr.requested_reviewers {
             for r in rs {
-                let github_user: model::GithubUserName = r.into();
-
-                let mut discord_users = Vec::new();
-                for (discord_user_id, user_config) in &cfg.users {
-                    if user_config.github_names.contains(&github_user) {
-                        discord_users.push(discord_user_id.clone());
-                    }
+                reviewers.push(Reviewer::new(cfg, r.into(), None));
+            }
+        }
+        if let Some(teams) = &pr.requested_teams {
+            for team in teams {
+                let Some(members) = cfg.github_teams.get(&team.slug) else {
+                    continue;
+                };
+                for member in members {
+                    reviewers.push(Reviewer::new(cfg, member.clone(), Some(team.slug.clone())));
                 }
+            }
+        }
 
-                reviewers.push(Reviewer {
-                    github_user,
-                    discord_users,
-                });
+        // Authors only hear about their PRs if they asked to, and not for
+        // drafts.
+        let mut authors = Vec::new();
+        if let (Some(user), false) = (&pr.user, pr.draft.unwrap_or_default()) {
+            let github_user: model::GithubUserName = user.as_ref().into();
+            for (discord_user_id, user_config) in &cfg.users {
+                if user_config.author_report && user_config.github_names.contains(&github_user) {
+                    authors.push(discord_user_id.clone());
+                }
             }
         }
 
         Pr {
-            url: github_pr_url(cfg, pr.number),
+            key: format!("{}#{}", repo, pr.number),
+            url: github_pr_url(&repo, pr.number),
             github_pr: pr,
+            repo: repo.clone(),
             reviewers,
+            authors,
+            last_reviews: HashMap::new(),
+            ci: CiStatus::Unknown,
+            code_owners: Vec::new(),
+            watchers: Vec::new(),
         }
-    })
+    });
+
+    prs.map_ok(move |pr| add_pr_details(github.clone(), pr, cfg, code_owners.clone()))
+        .try_buffered(CONCURRENT_DETAIL_LOOKUPS)
 }
@@ -0,0 +1,100 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::sync::Mutex;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// How long to stop asking Github for anything when it says we are going too
+/// fast without saying for how long. Github suggests at least a minute.
+const DEFAULT_BACKOFF: TimeDelta = TimeDelta::minutes(1);
+
+/// The last known state of the Github API quota for the bot's credentials.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct RateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    /// When the quota is next replenished.
+    pub reset: Option<DateTime<Utc>>,
+    /// Set when Github asks us to slow down even though there is quota
+    /// remaining (a "secondary" rate limit).
+    pub retry_after: Option<DateTime<Utc>>,
+    /// When any of the above was last heard from Github.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl RateLimit {
+    /// If requests to Github will be refused until some time after `now`,
+    /// returns that time.
+    pub fn exhausted_until(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let reset = match self.remaining {
+            Some(0) => self.reset,
+            _ => None,
+        };
+        reset.max(self.retry_after).filter(|until| *until > now)
+    }
+}
+
+/// Keeps track of the Github quota as responses come in.
+#[derive(Default)]
+pub struct RateLimitTracker {
+    state: Mutex<RateLimit>,
+}
+
+impl RateLimitTracker {
+    pub fn get(&self) -> RateLimit {
+        *self.state.lock().unwrap()
+    }
+
+    /// Records the quota from the headers that Github puts on every response.
+    pub fn record_headers(&self, headers: &http::HeaderMap) {
+        let header = |name| -> Option<u64> { headers.get(name)?.to_str().ok()?.parse().ok() };
+        let now = Utc::now();
+        let mut state = self.state.lock().unwrap();
+        if let Some(remaining) = header("x-ratelimit-remaining") {
+            state.limit = header("x-ratelimit-limit");
+            state.remaining = Some(remaining);
+            state.reset = header("x-ratelimit-reset")
+                .and_then(|secs| DateTime::from_timestamp(secs as i64, 0));
+            state.updated_at = Some(now);
+        }
+        if let Some(secs) = header("retry-after") {
+            state.retry_after = Some(now + TimeDelta::seconds(secs as i64));
+            state.updated_at = Some(now);
+        }
+    }
+
+    /// Records the quota from Github's `/rate_limit` endpoint.
+    pub fn record_rate(&self, rate: &octocrab::models::Rate) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = Some(rate.limit as u64);
+        state.remaining = Some(rate.remaining as u64);
+        state.reset = DateTime::from_timestamp(rate.reset as i64, 0);
+        state.updated_at = Some(Utc::now());
+    }
+
+    /// Records that Github refused a request because of its rate limit. If
+    /// the quota isn't known to be used up, it was a secondary rate limit.
+    pub fn record_limited(&self) {
+        let now = Utc::now();
+        let mut state = self.state.lock().unwrap();
+        if state.exhausted_until(now).is_none() {
+            state.retry_after = Some(now + DEFAULT_BACKOFF);
+            state.updated_at = Some(now);
+        }
+    }
+}
+
+/// Whether Github refused a request because of its rate limit, rather than
+/// for any other reason.
+pub fn is_rate_limit_error(e: &octocrab::Error) -> bool {
+    match e {
+        octocrab::Error::GitHub { source, .. } => {
+            (source.status_code == http::StatusCode::FORBIDDEN
+                || source.status_code == http::StatusCode::TOO_MANY_REQUESTS)
+                && source.message.to_lowercase().contains("rate limit")
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,236 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::stream::TryStreamExt;
+use octocrab::models::pulls::{Review, ReviewState};
+use octocrab::models::repos::RepoCommit;
+use octocrab::{Octocrab, Page};
+use serde::Deserialize;
+
+use super::pages::stream_pages;
+use crate::error::Error;
+use crate::model;
+
+/// The most recent review of a PR by one Github user.
+#[derive(Clone)]
+pub struct LastReview {
+    pub state: ReviewState,
+    pub submitted_at: Option<DateTime<Utc>>,
+    /// The head of the PR when it was reviewed.
+    pub commit_id: Option<String>,
+    /// The number of commits pushed to the PR after the review was submitted.
+    pub commits_since: usize,
+}
+
+/// When reviews of a PR were last requested, as recorded in its timeline.
+#[derive(Clone, Default)]
+pub struct ReviewRequests {
+    pub users: HashMap<model::GithubUserName, DateTime<Utc>>,
+    /// Requests from teams, keyed by the team's slug.
+    pub teams: HashMap<String, DateTime<Utc>>,
+}
+
+/// Where a requested review stands, from the point of view of the reviewer.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum ReviewStatus {
+    /// The reviewer has not reviewed the PR yet, or was asked to look again
+    /// after a review that did not request changes, or after the author
+    /// answered their requested changes without pushing.
+    NewRequest,
+    /// The author has pushed commits since the reviewer's last review.
+    AuthorUpdated,
+    /// The reviewer requested changes, and the author has not pushed any
+    /// commits since.
+    WaitingOnAuthor,
+}
+
+impl ReviewStatus {
+    /// `requested_at` is when the review was last requested, if known. Github
+    /// drops a reviewer from the requested reviewers when they submit a
+    /// review, so a request made after the last review means the author asked
+    /// them to look again.
+    pub fn from_last_review(
+        last_review: Option<&LastReview>,
+        requested_at: Option<DateTime<Utc>>,
+    ) -> Self {
+        let Some(last_review) = last_review else {
+            return ReviewStatus::NewRequest;
+        };
+        let requested_again = match (requested_at, last_review.submitted_at) {
+            (Some(requested_at), Some(submitted_at)) => requested_at > submitted_at,
+            _ => false,
+        };
+        if last_review.commits_since > 0 {
+            ReviewStatus::AuthorUpdated
+        } else if last_review.state == ReviewState::ChangesRequested && !requested_again {
+            ReviewStatus::WaitingOnAuthor
+        } else {
+            ReviewStatus::NewRequest
+        }
+    }
+}
+
+fn commits_since(commits: &[RepoCommit], review: &Review) -> usize {
+    if let Some(commit_id) = &review.commit_id {
+        if let Some(i) = commits.iter().position(|c| &c.sha == commit_id) {
+            return commits.len() - i - 1;
+        }
+    }
+
+    // The reviewed commit is no longer part of the PR, such as after a force
+    // push, so fall back to comparing commit times.
+    let Some(submitted_at) = review.submitted_at else {
+        return 0;
+    };
+    let newer = commits
+        .iter()
+        .filter(|c| {
+            let date = c.commit.committer.as_ref().and_then(|c| c.date);
+            date.is_some_and(|date| date > submitted_at)
+        })
+        .count();
+    if review.commit_id.is_some() {
+        newer.max(1)
+    } else {
+        newer
+    }
+}
+
+/// Finds the last review of the PR by each Github user that reviewed it, along
+/// with how many commits have been pushed to the PR since.
+pub(super) async fn get_last_reviews(
+    octo: Arc<Octocrab>,
+    repo_owner: &str,
+    repo_name: &str,
+    pr_number: u64,
+) -> Result<HashMap<model::GithubUserName, LastReview>, Error> {
+    let reviews_task = octo
+        .pulls(repo_owner, repo_name)
+        .list_reviews(pr_number)
+        .per_page(100)
+        .send()
+        .await;
+    let reviews: Vec<Review> = match reviews_task {
+        Ok(page) => {
+            stream_pages(octo.clone(), page, Error::FailedToGetReviews)
+                .try_collect()
+                .await?
+        }
+        Err(e) => return Err(Error::FailedToGetReviews(e)),
+    };
+
+    // Reviews are listed in chronological order, so later ones replace earlier
+    // ones.
+    let mut last_reviews: HashMap<model::GithubUserName, Review> = HashMap::new();
+    for review in reviews {
+        let Some(user) = &review.user else {
+            continue;
+        };
+        match review.state {
+            None | Some(ReviewState::Pending) | Some(ReviewState::Dismissed) => continue,
+            Some(_) => {}
+        }
+        last_reviews.insert(user.into(), review);
+    }
+    if last_reviews.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let commits_task = octo
+        .pulls(repo_owner, repo_name)
+        .pr_commits(pr_number)
+        .per_page(100)
+        .send()
+        .await;
+    let commits: Vec<RepoCommit> = match commits_task {
+        Ok(page) => {
+            stream_pages(octo.clone(), page, Error::FailedToGetReviews)
+                .try_collect()
+                .await?
+        }
+        Err(e) => return Err(Error::FailedToGetReviews(e)),
+    };
+
+    Ok(last_reviews
+        .into_iter()
+        .map(|(github_user, review)| {
+            let last_review = LastReview {
+                commits_since: commits_since(&commits, &review),
+                state: review.state.unwrap(),
+                submitted_at: review.submitted_at,
+                commit_id: review.commit_id,
+            };
+            (github_user, last_review)
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct Login {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct Slug {
+    slug: String,
+}
+
+/// The parts of a timeline event that say who a review was requested from.
+/// Octocrab's own model leaves out requests from teams.
+#[derive(Deserialize)]
+struct TimelineEvent {
+    event: Option<String>,
+    created_at: Option<DateTime<Utc>>,
+    requested_reviewer: Option<Login>,
+    requested_team: Option<Slug>,
+}
+
+/// Finds when each review of the PR was last requested, from the
+/// `review_requested` events in its timeline.
+pub(super) async fn get_review_requests(
+    octo: Arc<Octocrab>,
+    repo_owner: &str,
+    repo_name: &str,
+    pr_number: u64,
+) -> Result<ReviewRequests, Error> {
+    let route = format!(
+        "/repos/{}/{}/issues/{}/timeline",
+        repo_owner, repo_name, pr_number
+    );
+    let events: Vec<TimelineEvent> = match octo
+        .get::<Page<TimelineEvent>, _, _>(route, Some(&[("per_page", 100)]))
+        .await
+    {
+        Ok(page) => {
+            stream_pages(octo.clone(), page, Error::FailedToGetTimeline)
+                .try_collect()
+                .await?
+        }
+        Err(e) => return Err(Error::FailedToGetTimeline(e)),
+    };
+
+    // Events are listed in chronological order, so a request made again after
+    // a review replaces the earlier one.
+    let mut requests = ReviewRequests::default();
+    for event in events {
+        let (Some("review_requested"), Some(created_at)) =
+            (event.event.as_deref(), event.created_at)
+        else {
+            continue;
+        };
+        if let Some(user) = event.requested_reviewer {
+            requests
+                .users
+                .insert(model::GithubUserName::from_str(&user.login), created_at);
+        }
+        if let Some(team) = event.requested_team {
+            requests.teams.insert(team.slug, created_at);
+        }
+    }
+    Ok(requests)
+}
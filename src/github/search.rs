@@ -0,0 +1,106 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::sync::Arc;
+
+use futures::stream::{StreamExt, TryStreamExt};
+use octocrab::models::issues::Issue;
+
+use super::source::GithubSource;
+use crate::error::Error;
+use crate::model;
+
+/// The most results of a saved search that are reported. Searches can match
+/// far more than fits in a report.
+pub const MAX_SEARCH_RESULTS: usize = 25;
+
+/// An issue or PR found by a saved search.
+pub struct SearchResult {
+    pub github_issue: Issue,
+    /// The repository that the issue or PR is in.
+    pub repo: model::RepoConfig,
+    pub url: String,
+}
+
+impl SearchResult {
+    pub fn is_pr(&self) -> bool {
+        self.github_issue.pull_request.is_some()
+    }
+}
+
+/// Limits `query` to the watched `repos`, unless it already says where to
+/// search.
+fn scope_query(query: &str, repos: &[model::RepoConfig]) -> String {
+    let scoped = query.split_whitespace().any(|term| {
+        let term = term.trim_start_matches('-');
+        ["repo:", "org:", "user:"]
+            .iter()
+            .any(|qualifier| term.starts_with(qualifier))
+    });
+    let mut query = query.trim().to_string();
+    if !scoped {
+        for repo in repos {
+            query.push_str(&format!(" repo:{}", repo));
+        }
+    }
+    query
+}
+
+/// Runs a saved search, limited to the watched `repos` unless the query says
+/// otherwise.
+pub async fn search(
+    github: Arc<dyn GithubSource>,
+    query: &str,
+    repos: &[model::RepoConfig],
+) -> Result<Vec<SearchResult>, Error> {
+    github
+        .search_issues(&scope_query(query, repos))
+        .await?
+        .take(MAX_SEARCH_RESULTS)
+        .map_ok(|issue| {
+            // The repository URL ends in `/repos/{owner}/{name}`.
+            let mut segments = issue.repository_url.path().rsplit('/');
+            let name = segments.next().unwrap_or_default().to_string();
+            let owner = segments.next().unwrap_or_default().to_string();
+            SearchResult {
+                url: issue.html_url.to_string(),
+                github_issue: issue,
+                repo: model::RepoConfig { owner, name },
+            }
+        })
+        .try_collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scoped_queries() {
+        let repos = [
+            model::RepoConfig {
+                owner: "carbon-language".to_string(),
+                name: "carbon-lang".to_string(),
+            },
+            model::RepoConfig {
+                owner: "carbon-language".to_string(),
+                name: "carbon-tools".to_string(),
+            },
+        ];
+        assert_eq!(
+            scope_query("is:issue no:assignee ", &repos),
+            "is:issue no:assignee repo:carbon-language/carbon-lang \
+            repo:carbon-language/carbon-tools"
+        );
+        assert_eq!(
+            scope_query("is:pr org:carbon-language", &repos),
+            "is:pr org:carbon-language"
+        );
+        assert_eq!(
+            scope_query("is:pr -repo:carbon-language/carbon-lang", &repos),
+            "is:pr -repo:carbon-language/carbon-lang"
+        );
+    }
+}
@@ -0,0 +1,121 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use octocrab::models::issues::Issue;
+use octocrab::models::pulls::PullRequest;
+
+use super::checks::CiStatus;
+use super::codeowners::CodeOwners;
+use super::rate_limit::RateLimit;
+use super::reviews::{LastReview, ReviewRequests};
+use super::webhook::WebhookItem;
+use crate::error::Error;
+use crate::model;
+
+/// A lazily fetched list of items from Github.
+pub type ItemStream<T> = BoxStream<'static, Result<T, Error>>;
+
+/// Where the bot gets its view of Github from. All access to Github goes
+/// through this trait, so that it can be replaced by fixtures in tests.
+pub trait GithubSource: Send + Sync {
+    /// Lists the open PRs in a repository, least recently updated first.
+    fn list_open_prs<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+    ) -> BoxFuture<'a, Result<ItemStream<PullRequest>, Error>>;
+
+    /// Lists the open issues with the `label` in a repository, least recently
+    /// updated first.
+    fn list_open_issues<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        label: &'a str,
+    ) -> BoxFuture<'a, Result<ItemStream<Issue>, Error>>;
+
+    /// Searches for issues and PRs, with Github's search syntax.
+    fn search_issues<'a>(
+        &'a self,
+        query: &'a str,
+    ) -> BoxFuture<'a, Result<ItemStream<Issue>, Error>>;
+
+    /// Finds the last review of a PR by each Github user that reviewed it, as
+    /// of when the PR was last updated, `updated_at`.
+    fn get_last_reviews<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        pr_number: u64,
+        updated_at: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'a, Result<HashMap<model::GithubUserName, LastReview>, Error>>;
+
+    /// Finds when each review of a PR was last requested, as of when the PR
+    /// was last updated, `updated_at`.
+    fn get_review_requests<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        pr_number: u64,
+        updated_at: Option<DateTime<Utc>>,
+    ) -> BoxFuture<'a, Result<ReviewRequests, Error>>;
+
+    /// Finds who owns which files in a repository, from its CODEOWNERS file,
+    /// or `None` if it doesn't have one.
+    fn get_code_owners<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+    ) -> BoxFuture<'a, Result<Option<Arc<CodeOwners>>, Error>>;
+
+    /// Lists the paths of the files changed by a PR, as of the commit
+    /// `head_sha`.
+    fn list_pr_files<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        pr_number: u64,
+        head_sha: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<String>, Error>>;
+
+    /// Finds the combined CI result for the commit `sha`.
+    fn get_ci_status<'a>(
+        &'a self,
+        repo_owner: &'a str,
+        repo_name: &'a str,
+        sha: &'a str,
+    ) -> BoxFuture<'a, Result<CiStatus, Error>>;
+
+    /// Lists the members of a team in a Github organization.
+    fn get_team_members<'a>(
+        &'a self,
+        org: &'a str,
+        team_slug: &'a str,
+    ) -> BoxFuture<'a, Result<Vec<model::GithubUserName>, Error>>;
+
+    /// Tells the source that a repository changed on Github, so that anything
+    /// cached from it is fetched again.
+    fn repo_changed(&self, repo_owner: &str, repo_name: &str);
+
+    /// Applies a PR or issue from a webhook to what is cached from a
+    /// repository, so that the change is seen without fetching the lists of
+    /// the repository again.
+    fn apply_change(&self, repo_owner: &str, repo_name: &str, item: &WebhookItem);
+
+    /// Drops what is cached but no longer needed, such as the details of PRs
+    /// that were closed. Called on each poll.
+    fn prune_caches(&self);
+
+    /// The last known state of the Github quota.
+    fn rate_limit(&self) -> RateLimit;
+
+    /// Asks Github for the state of the quota. This does not use up any quota.
+    fn refresh_rate_limit(&self) -> BoxFuture<'_, Result<RateLimit, Error>>;
+}
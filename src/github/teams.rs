@@ -0,0 +1,49 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use std::sync::Arc;
+
+use futures::stream::TryStreamExt;
+use octocrab::Octocrab;
+
+use super::pages::stream_pages;
+use crate::error::Error;
+use crate::model;
+
+/// Returns the team slug without any `@org/` prefix, as teams are written in
+/// mentions.
+pub fn normalize_team_slug(team: &str) -> String {
+    let team = team.trim().trim_start_matches('@');
+    match team.split_once('/') {
+        Some((_org, slug)) => slug.to_string(),
+        None => team.to_string(),
+    }
+}
+
+/// Returns the organization of a team written as `@org/slug`, if it has one.
+pub fn team_org(team: &str) -> Option<String> {
+    let team = team.trim().trim_start_matches('@');
+    team.split_once('/').map(|(org, _slug)| org.to_string())
+}
+
+pub(super) async fn get_team_members(
+    octo: Arc<Octocrab>,
+    org: &str,
+    team_slug: &str,
+) -> Result<Vec<model::GithubUserName>, Error> {
+    let members_task = octo
+        .teams(org)
+        .members(team_slug)
+        .per_page(100)
+        .send()
+        .await;
+    let members = match members_task {
+        Ok(members) => members,
+        Err(e) => return Err(Error::FailedToGetTeamMembers(e)),
+    };
+    stream_pages(octo, members, Error::FailedToGetTeamMembers)
+        .map_ok(|author| model::GithubUserName::from(&author))
+        .try_collect()
+        .await
+}
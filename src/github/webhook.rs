@@ -0,0 +1,170 @@
+// Part of the Carbon Language project, under the Apache License v2.0 with LLVM
+// Exceptions. See /LICENSE for license information.
+// SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
+
+use octocrab::models::issues::Issue;
+use octocrab::models::pulls::PullRequest;
+use ring::hmac;
+use serde::Deserialize;
+
+use crate::error::Error;
+
+/// The webhook events that can change what is reported, by their
+/// `X-GitHub-Event` header.
+const REPORTED_EVENTS: &[&str] = &["pull_request", "pull_request_review", "issues"];
+
+/// A PR or issue as it is after a change.
+#[derive(Debug, PartialEq)]
+pub enum WebhookItem {
+    Pr(Box<PullRequest>),
+    Issue(Box<Issue>),
+}
+
+/// A change to a repository, announced by a Github webhook.
+#[derive(Debug, PartialEq)]
+pub struct WebhookEvent {
+    pub repo_owner: String,
+    pub repo_name: String,
+    /// The changed PR or issue, or `None` if the payload doesn't carry all of
+    /// it.
+    pub item: Option<WebhookItem>,
+}
+
+#[derive(Deserialize)]
+struct PayloadOwner {
+    login: String,
+}
+
+#[derive(Deserialize)]
+struct PayloadRepository {
+    name: String,
+    owner: PayloadOwner,
+}
+
+/// The parts of a webhook payload that are common to all the reported events,
+/// and the PR or issue that the event is about. Those are parsed separately,
+/// so that a payload that leaves out some of their fields still names the
+/// repository.
+#[derive(Deserialize)]
+struct Payload {
+    repository: PayloadRepository,
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+    #[serde(default)]
+    issue: Option<serde_json::Value>,
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    // An odd length leaves half a byte at the end, which `get` rejects.
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+/// Checks the `X-Hub-Signature-256` header of a webhook, which Github computes
+/// from the body and the secret that the webhook was set up with.
+pub fn verify_webhook_signature(secret: &[u8], body: &[u8], signature: &str) -> bool {
+    let Some(tag) = signature.strip_prefix("sha256=").and_then(decode_hex) else {
+        return false;
+    };
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret);
+    hmac::verify(&key, body, &tag).is_ok()
+}
+
+/// Finds the repository that a webhook is about, or `None` if the event can't
+/// change what is reported.
+pub fn parse_webhook(event: &str, body: &[u8]) -> Result<Option<WebhookEvent>, Error> {
+    if !REPORTED_EVENTS.contains(&event) {
+        return Ok(None);
+    }
+    match serde_json::from_slice::<Payload>(body) {
+        Ok(payload) => {
+            let pr = payload
+                .pull_request
+                .and_then(|pr| serde_json::from_value(pr).ok())
+                .map(WebhookItem::Pr);
+            let issue = payload
+                .issue
+                .and_then(|issue| serde_json::from_value(issue).ok())
+                .map(WebhookItem::Issue);
+            Ok(Some(WebhookEvent {
+                repo_owner: payload.repository.owner.login,
+                repo_name: payload.repository.name,
+                item: pr.or(issue),
+            }))
+        }
+        Err(e) => Err(Error::WebhookPayloadInvalid(
+            event.to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PR_PAYLOAD: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/testdata/webhooks/pull_request.json"
+    ));
+
+    #[test]
+    fn signature() {
+        // The example from Github's documentation on validating webhooks.
+        let secret = b"It's a Secret to Everybody";
+        let body = b"Hello, World!";
+        let signature = "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17";
+        assert!(verify_webhook_signature(secret, body, signature));
+        assert!(!verify_webhook_signature(b"wrong secret", body, signature));
+        assert!(!verify_webhook_signature(
+            secret,
+            b"Hello, World?",
+            signature
+        ));
+        assert!(!verify_webhook_signature(secret, body, &signature[7..]));
+        assert!(!verify_webhook_signature(secret, body, "sha256=zz"));
+    }
+
+    const ISSUE_PAYLOAD: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/testdata/webhooks/issues.json"
+    ));
+
+    const REVIEW_PAYLOAD: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/testdata/webhooks/pull_request_review.json"
+    ));
+
+    #[test]
+    fn parse() {
+        let event = parse_webhook("pull_request", PR_PAYLOAD.as_bytes())
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.repo_owner, "carbon-language");
+        assert_eq!(event.repo_name, "carbon-lang");
+        let Some(WebhookItem::Pr(pr)) = event.item else {
+            panic!("{:?}", event.item);
+        };
+        assert_eq!(pr.number, 101);
+
+        let event = parse_webhook("issues", ISSUE_PAYLOAD.as_bytes())
+            .unwrap()
+            .unwrap();
+        let Some(WebhookItem::Issue(issue)) = event.item else {
+            panic!("{:?}", event.item);
+        };
+        assert_eq!(issue.number, 201);
+
+        // A PR that is missing fields only says that the repository changed.
+        let event = parse_webhook("pull_request_review", REVIEW_PAYLOAD.as_bytes())
+            .unwrap()
+            .unwrap();
+        assert_eq!(event.repo_name, "carbon-lang");
+        assert_eq!(event.item, None);
+
+        assert_eq!(parse_webhook("ping", PR_PAYLOAD.as_bytes()).unwrap(), None);
+        assert!(parse_webhook("issues", b"{}").is_err());
+    }
+}
@@ -7,7 +7,7 @@ mod error;
 mod github;
 mod model;
 
-use std::{process::ExitCode, sync::Arc};
+use std::{path::Path, process::ExitCode, sync::Arc};
 
 use crate::error::Error;
 
@@ -22,19 +22,35 @@ async fn main() -> ExitCode {
         }
     };
 
-    let data = Arc::new(discord::DiscordData::new(cfg));
-
-    println!("Running...");
-    let result = loop {
-        tokio::select! {
-            result = discord::run(data.clone()) => {
-                break result;
+    let github: Arc<dyn github::GithubSource> = match std::env::var("FIZZ_GITHUB_FIXTURE") {
+        Ok(path) => {
+            println!("Using fake Github from {}", path);
+            match github::FakeGithub::load(Path::new(&path)) {
+                Ok(fake) => Arc::new(fake),
+                Err(e) => {
+                    eprintln!("ERROR: {}", e);
+                    return ExitCode::FAILURE;
+                }
             }
-            _ = tokio::signal::ctrl_c() => {
-                println!("\nReceived interrupt signal, shutting down.");
-                discord::stop().await;
-                break Ok(())
+        }
+        Err(_) => match github::new_client() {
+            Ok(octo) => Arc::new(github::OctocrabSource::new(octo)),
+            Err(e) => {
+                eprintln!("ERROR: {}", e);
+                return ExitCode::FAILURE;
             }
+        },
+    };
+
+    let data = Arc::new(discord::DiscordData::new(cfg, github));
+
+    println!("Running...");
+    let result = tokio::select! {
+        result = discord::run(data.clone()) => result,
+        _ = tokio::signal::ctrl_c() => {
+            println!("\nReceived interrupt signal, shutting down.");
+            discord::stop().await;
+            Ok(())
         }
     };
 
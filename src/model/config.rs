@@ -2,11 +2,11 @@
 // Exceptions. See /LICENSE for license information.
 // SPDX-License-Identifier: Apache-2.0 WITH LLVM-exception
 
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono::{DateTime, Duration, NaiveDate, NaiveTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, path::PathBuf};
 
-use super::{DiscordChannelId, DiscordGuildId, DiscordUserId, GithubUserName};
+use super::{DiscordChannelId, DiscordGuildId, DiscordMessageId, DiscordUserId, GithubUserName};
 use crate::error::Error;
 
 const APP_NAME: &str = "fizz";
@@ -22,6 +22,10 @@ pub struct UserConfig {
     /// Whether the user wants pings for leads issues.
     #[serde(default)]
     pub lead: bool,
+    /// Whether the user wants reports of PRs that nobody has been asked to
+    /// review.
+    #[serde(default)]
+    pub triager: bool,
     /// The timezone for the user, where they are currently working from. Dates
     /// and times specified by the user are all relative to this.
     #[serde(default)]
@@ -33,6 +37,30 @@ pub struct UserConfig {
     /// The times at which Fizz should alert the user about outstanding reviews.
     #[serde(default = "default_report_times")]
     pub report_times: Vec<NaiveTime>,
+    /// Whether the user wants a report on the status of the PRs they authored.
+    #[serde(default)]
+    pub author_report: bool,
+    /// The number of days after which an authored PR that is waiting for
+    /// review is called out in the user's report.
+    #[serde(default = "default_author_report_stale_days")]
+    pub author_report_stale_days: u32,
+    /// The number of days after which a review requested from the user is
+    /// marked as overdue in their report.
+    #[serde(default = "default_review_overdue_days")]
+    pub review_overdue_days: u32,
+    /// Paths in the repositories, like `toolchain/check/**`, that the user
+    /// wants to hear about every PR for.
+    #[serde(default)]
+    pub watched_paths: Vec<String>,
+    /// The user's own saved searches, reported at their report times.
+    #[serde(default)]
+    pub saved_searches: Vec<SavedSearch>,
+    /// Whether to leave PRs with failing CI out of the user's review reports.
+    #[serde(default)]
+    pub hide_failing_ci: bool,
+    /// Where the user's reports are sent.
+    #[serde(default)]
+    pub delivery: Delivery,
     /// A date on which the user will be back from being away. For dates up to
     /// and including this date, they are away.
     #[serde(default)]
@@ -44,6 +72,25 @@ pub struct UserConfig {
     #[serde(default)]
     pub friendly_name: String,
 
+    /// The PRs that the user has reviewed, keyed by `owner/name#number`, with
+    /// the head of the PR when they last reviewed it. They hear about new
+    /// commits to these PRs even when their review is no longer requested.
+    #[serde(default)]
+    pub reviewed_prs: HashMap<String, String>,
+
+    /// Set when the user asks for their next report to be posted afresh,
+    /// pinging them, rather than edited in place.
+    #[serde(default)]
+    pub ping_next_report: bool,
+    /// The reports that fizz sent the user in direct messages, keyed by the
+    /// header of their section.
+    #[serde(default)]
+    pub dm_report_messages: HashMap<String, PostedSection>,
+    /// The user's thread in the report channel, when the guild uses
+    /// `ReportThreads`.
+    #[serde(default)]
+    pub report_thread: Option<ReportThread>,
+
     /// The time when the last weekly report of non-urgent leads issues was
     // made for the user. Used to throttle these reports for each user.
     #[serde(default)]
@@ -54,6 +101,14 @@ fn default_workdays() -> String {
     "12345".to_string()
 }
 
+fn default_author_report_stale_days() -> u32 {
+    3
+}
+
+fn default_review_overdue_days() -> u32 {
+    3
+}
+
 fn default_report_times() -> Vec<NaiveTime> {
     vec![
         NaiveTime::from_hms_opt(9, 0, 0).unwrap(),
@@ -67,27 +122,307 @@ impl UserConfig {
             friendly_name,
             github_names: Default::default(),
             lead: Default::default(),
+            triager: Default::default(),
             timezone: Default::default(),
             workdays: default_workdays(),
             report_times: default_report_times(),
+            author_report: Default::default(),
+            author_report_stale_days: default_author_report_stale_days(),
+            review_overdue_days: default_review_overdue_days(),
+            watched_paths: Default::default(),
+            saved_searches: Default::default(),
+            hide_failing_ci: Default::default(),
             away_until: Default::default(),
             last_weekly_report: Default::default(),
+            reviewed_prs: Default::default(),
+            ping_next_report: Default::default(),
+            delivery: Default::default(),
+            dm_report_messages: Default::default(),
+            report_thread: Default::default(),
+        }
+    }
+}
+
+/// A Github repository.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct RepoConfig {
+    pub owner: String,
+    pub name: String,
+}
+
+impl RepoConfig {
+    /// Whether this is the repository `owner/name`. Github ignores case in
+    /// repository names.
+    pub fn is(&self, owner: &str, name: &str) -> bool {
+        self.owner.eq_ignore_ascii_case(owner) && self.name.eq_ignore_ascii_case(name)
+    }
+}
+
+impl std::fmt::Display for RepoConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.owner, self.name)
+    }
+}
+
+/// How urgent a leads issue is, which decides how often the leads hear about
+/// it. The most urgent comes first.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Urgency {
+    /// Reported as soon as it is seen, and then in every report.
+    Immediate,
+    /// Reported in every report.
+    EveryReport,
+    /// Reported once a week.
+    Weekly,
+    /// Never reported.
+    Never,
+}
+
+/// The Github labels that pick out leads issues, and say how urgent they are.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IssueLabels {
+    /// The label on issues for the leads.
+    pub leads: String,
+    /// The urgency of leads issues with each label. When an issue has more
+    /// than one of these labels, the most urgent one wins.
+    #[serde(default)]
+    pub urgency: HashMap<String, Urgency>,
+    /// The urgency of leads issues without any of the `urgency` labels.
+    pub unlabeled: Urgency,
+}
+
+impl Default for IssueLabels {
+    fn default() -> Self {
+        Self {
+            leads: "leads question".to_string(),
+            urgency: HashMap::from([
+                ("blocking work".to_string(), Urgency::EveryReport),
+                ("long term issue".to_string(), Urgency::Never),
+            ]),
+            unlabeled: Urgency::Weekly,
+        }
+    }
+}
+
+impl IssueLabels {
+    /// The urgency of a leads issue with the `labels`.
+    pub fn urgency_of<'a>(&self, labels: impl IntoIterator<Item = &'a str>) -> Urgency {
+        labels
+            .into_iter()
+            .filter_map(|label| self.urgency.get(label).copied())
+            .min()
+            .unwrap_or(self.unlabeled)
+    }
+}
+
+/// Where a user's reports are sent.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Delivery {
+    /// The guild's report channel.
+    #[default]
+    Channel,
+    /// Direct messages to the user.
+    Dm,
+    Both,
+}
+
+impl Delivery {
+    pub fn to_channel(self) -> bool {
+        self != Delivery::Dm
+    }
+
+    pub fn to_dm(self) -> bool {
+        self != Delivery::Channel
+    }
+}
+
+/// How reports are rendered.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    /// Markdown text.
+    #[default]
+    Text,
+    /// An embed for each section, with a field for each PR or issue.
+    Embeds,
+}
+
+/// Whether each user's reports go in a thread of their own in the report
+/// channel, and what kind.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportThreads {
+    /// Reports are posted in the report channel itself.
+    #[default]
+    Off,
+    Public,
+    Private,
+}
+
+/// A user's thread for their reports, and what it was made for, so that it
+/// makes way for a new one when the guild changes either.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct ReportThread {
+    pub thread_id: DiscordChannelId,
+    /// The report channel that the thread is in.
+    pub parent_id: DiscordChannelId,
+    pub kind: ReportThreads,
+}
+
+/// How often a saved search is reported.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Cadence {
+    /// Once a day. For a user, this is at the first of their report times.
+    #[default]
+    Daily,
+    /// Once a week.
+    Weekly,
+}
+
+/// A Github search that is reported in a section of its own.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct SavedSearch {
+    pub name: String,
+    /// The Github search query, like `is:issue label:"good first issue"
+    /// no:assignee`. It is limited to the watched repositories unless it says
+    /// otherwise.
+    pub query: String,
+    #[serde(default)]
+    pub cadence: Cadence,
+
+    // Internal state.
+    /// When the search was last reported.
+    #[serde(default)]
+    pub last_reported_at: Option<DateTime<Utc>>,
+}
+
+impl SavedSearch {
+    /// Whether the search should be reported again at `now`.
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        let period = match self.cadence {
+            Cadence::Daily => Duration::days(1),
+            Cadence::Weekly => Duration::days(7),
+        };
+        // Report times come around a little earlier or later each time,
+        // depending on when the bot wakes up.
+        let slack = Duration::hours(1);
+        match self.last_reported_at {
+            Some(last_reported_at) => now - last_reported_at >= period - slack,
+            None => true,
         }
     }
 }
 
+/// When reviews that have been waiting too long are escalated, and to where.
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub struct EscalationConfig {
+    /// The number of workdays a review can be outstanding before it is
+    /// escalated, counted with each reviewer's workdays. `None` turns off
+    /// escalation.
+    #[serde(default)]
+    pub after_workdays: Option<u32>,
+    /// The channel to post escalations in. When empty, they are posted in the
+    /// report channel, pinging the leads.
+    #[serde(default)]
+    pub channel_id: DiscordChannelId,
+    /// The friendly name of `channel_id`, when it was set.
+    #[serde(default)]
+    pub channel_name: String,
+}
+
+/// How often a PR has been escalated, to space out repeated escalations.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct Escalated {
+    pub count: u32,
+    pub last_escalated_at: DateTime<Utc>,
+}
+
+/// A message that fizz posted in a report.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct PostedMessage {
+    pub message_id: DiscordMessageId,
+    pub content: String,
+    /// The message's embed as JSON, if it has one.
+    #[serde(default)]
+    pub embed: Option<String>,
+}
+
+/// The messages that fizz posted for one section of a report, which later
+/// reports edit in place.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct PostedSection {
+    pub channel_id: DiscordChannelId,
+    /// The user that the section is for, or `None` for the guild's sections.
+    #[serde(default)]
+    pub discord_user_id: Option<DiscordUserId>,
+    pub messages: Vec<PostedMessage>,
+    /// What the section listed, like `owner/name#number` for PRs and issues.
+    /// The section is posted afresh, pinging the user again, once it lists
+    /// something new.
+    #[serde(default)]
+    pub keys: Vec<String>,
+}
+
 #[derive(Serialize, Deserialize, Default)]
 pub struct GuildConfig {
-    pub repo_owner: String,
-    pub repo_name: String,
+    /// The Github repositories to report on.
+    #[serde(default)]
+    pub repos: Vec<RepoConfig>,
+    /// The repository from before a guild could watch more than one. It is
+    /// moved into `repos` when the config is loaded.
+    #[serde(default, rename = "repo_owner", skip_serializing)]
+    pub legacy_repo_owner: String,
+    #[serde(default, rename = "repo_name", skip_serializing)]
+    pub legacy_repo_name: String,
     /// A stable discord channel identifier.
     pub report_channel_id: DiscordChannelId,
     /// The friendly name of the channel, when it was added. It may not match
     /// reality if the channel is renamed later.
     pub report_channel_name: String,
+    /// The members of Github teams, keyed by the team's slug. Reviews requested
+    /// from a team are sent to each of its members.
+    #[serde(default)]
+    pub github_teams: HashMap<String, Vec<GithubUserName>>,
+    /// How leads issues are labelled on Github.
+    #[serde(default)]
+    pub issue_labels: IssueLabels,
+    /// How reviews that have been waiting too long are escalated.
+    #[serde(default)]
+    pub escalation: EscalationConfig,
+    /// Whether users get a thread of their own in the report channel.
+    #[serde(default)]
+    pub report_threads: ReportThreads,
+    /// How reports are rendered.
+    #[serde(default)]
+    pub report_format: ReportFormat,
+    /// Saved searches that are reported in the report channel, for everyone.
+    #[serde(default)]
+    pub saved_searches: Vec<SavedSearch>,
     // Configuration for users in the Discord guild (aka server).
     #[serde(default)]
     pub users: HashMap<DiscordUserId, UserConfig>,
+
+    // Internal state.
+    /// The `Urgency::Immediate` leads issues that the leads have been told
+    /// about, as `owner/name#number`. Issues leave the list once they are no
+    /// longer open and immediately urgent.
+    #[serde(default)]
+    pub announced_issues: Vec<String>,
+    /// The PRs that have been escalated, keyed by `owner/name#number`. PRs
+    /// leave the map once no review of them is overdue.
+    #[serde(default)]
+    pub escalated_prs: HashMap<String, Escalated>,
+    /// The report messages that fizz posted, keyed by the header of their
+    /// section. Headers name the user that the section is for, if any.
+    #[serde(default)]
+    pub report_messages: HashMap<String, PostedSection>,
+    /// Whether the reports from before fizz kept track of `report_messages`
+    /// have been deleted from the report channel.
+    #[serde(default)]
+    pub legacy_reports_swept: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -98,7 +433,7 @@ pub struct Config {
 }
 
 pub fn app_name() -> &'static str {
-    return APP_NAME;
+    APP_NAME
 }
 
 #[cfg(unix)]
@@ -144,7 +479,7 @@ pub fn load() -> Result<Config, Error> {
         Ok(data) => data,
         Err(io) => return Err(Error::IoError(Some(file_path), io)),
     };
-    let config: Config = match toml::from_str(&data) {
+    let mut config: Config = match toml::from_str(&data) {
         Ok(config) => config,
         Err(e) => {
             return Err(Error::ConfigParsingError(
@@ -159,6 +494,13 @@ pub fn load() -> Result<Config, Error> {
             format!("Config file has unknown version {}", config.version),
         ));
     }
+    for guild_config in config.guilds.values_mut() {
+        let owner = std::mem::take(&mut guild_config.legacy_repo_owner);
+        let name = std::mem::take(&mut guild_config.legacy_repo_name);
+        if !owner.is_empty() && !name.is_empty() && guild_config.repos.is_empty() {
+            guild_config.repos.push(RepoConfig { owner, name });
+        }
+    }
     Ok(config)
 }
 
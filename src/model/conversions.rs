@@ -48,6 +48,19 @@ impl From<serenity::GuildChannel> for model::DiscordChannelId {
     }
 }
 
+/// Direct messages are not in a guild, so the channel's guild is empty.
+impl From<&serenity::PrivateChannel> for model::DiscordChannelId {
+    fn from(channel: &serenity::PrivateChannel) -> Self {
+        Self(Default::default(), format!("{}", channel.id))
+    }
+}
+
+impl From<serenity::MessageId> for model::DiscordMessageId {
+    fn from(id: serenity::MessageId) -> Self {
+        Self(format!("{}", id))
+    }
+}
+
 impl From<serenity::User> for model::UserConfig {
     fn from(value: serenity::User) -> Self {
         model::UserConfig::new(value.name.clone())
@@ -77,6 +90,13 @@ impl From<&model::DiscordChannelId> for serenity::ChannelId {
     }
 }
 
+impl From<&model::DiscordMessageId> for serenity::MessageId {
+    fn from(value: &model::DiscordMessageId) -> Self {
+        use std::str::FromStr;
+        serenity::MessageId::from_str(&value.0).expect("failed to make MessageId from model value")
+    }
+}
+
 impl From<model::DiscordUserId> for serenity::UserId {
     fn from(value: model::DiscordUserId) -> Self {
         use std::str::FromStr;
@@ -34,13 +34,13 @@ pub fn discord_user_report_times(
             // Get the `time` during `date` for the user. Then convert them to UTC
             // time.
             NaiveDateTime::new(date, time)
-                .and_local_timezone(user_timezone.clone())
+                .and_local_timezone(*user_timezone)
                 .map(|dt| dt.with_timezone(&Utc {}))
                 .single()
         };
 
         for user_time in &user_config.report_times {
-            if let Some(time) = utc_datetime(user_today, user_time.clone()) {
+            if let Some(time) = utc_datetime(user_today, *user_time) {
                 out.push(time);
             }
         }
@@ -49,6 +49,20 @@ pub fn discord_user_report_times(
     out
 }
 
+/// Whether the user is away today, in their timezone.
+pub fn discord_user_is_away(
+    guild_config: &model::GuildConfig,
+    discord_user_id: &model::DiscordUserId,
+) -> bool {
+    let Some(user_config) = guild_config.users.get(discord_user_id) else {
+        return false;
+    };
+    let user_today = chrono::Local::now()
+        .with_timezone(&user_config.timezone)
+        .date_naive();
+    user_today <= user_config.away_until.unwrap_or(NaiveDate::MIN)
+}
+
 pub fn discord_user_weekly_report_needed(
     guild_config: &model::GuildConfig,
     discord_user_id: &model::DiscordUserId,
@@ -72,3 +86,29 @@ pub fn discord_user_weekly_report_needed(
     let days_since = (user_today - user_last_report).num_days();
     days_since >= 7
 }
+
+/// The number of the user's workdays that have started since `since`, in
+/// their timezone, up to and including the day of `now`.
+pub fn discord_user_workdays_since(
+    guild_config: &model::GuildConfig,
+    discord_user_id: &model::DiscordUserId,
+    since: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> u32 {
+    let Some(user_config) = guild_config.users.get(discord_user_id) else {
+        return 0;
+    };
+
+    let user_timezone = &user_config.timezone;
+    let user_today = now.with_timezone(user_timezone).date_naive();
+    let mut day = since.with_timezone(user_timezone).date_naive();
+    let mut workdays = 0;
+    while day < user_today {
+        day = day.succ_opt().unwrap_or(user_today);
+        let day_number = (day.weekday().number_from_sunday() - 1).to_string();
+        if user_config.workdays.contains(&day_number) {
+            workdays += 1;
+        }
+    }
+    workdays
+}
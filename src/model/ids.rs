@@ -37,6 +37,10 @@ impl DiscordChannelId {
     }
 }
 
+/// A stable discord message identifier, within its channel.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default, Debug)]
+pub struct DiscordMessageId(pub String);
+
 /// A github user name.
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Default, Debug)]
 pub struct GithubUserName(pub String);